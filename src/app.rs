@@ -1,19 +1,100 @@
 use anathema::{
-    component::Component,
+    component::{Component, Context},
     state::{State, Value},
 };
-use bb_anathema_components::BBAppComponent;
 
-pub struct App;
+use crate::{
+    game::{controller::ControllerKind, simulation::MIN_FIELD_SIZE},
+    highscores::GameMode,
+};
+
+/// Rows the game scene's header takes above the field.
+const HEADER_HEIGHT: u16 = 2;
+/// Smallest terminal the game can be played in, anything smaller shows a
+/// message until the terminal grows again.
+pub const MIN_WIDTH: u16 = MIN_FIELD_SIZE.x as u16;
+pub const MIN_HEIGHT: u16 = MIN_FIELD_SIZE.y as u16 + HEADER_HEIGHT;
+/// Largest seed a game can have. Templates hand numbers around as `i64`, so
+/// anything bigger would show up negative on the end screen.
+pub const MAX_SEED: u64 = i64::MAX as u64;
+
+pub struct App {
+    /// Seed from the command line, when missing every game gets a random one.
+    seed: Option<u64>,
+    /// Skip the splash scene and go straight to watching a replay.
+    replaying: bool,
+    mode: GameMode,
+    /// The score to go back to when the level is restarted.
+    level_start_score: u32,
+    /// The scene to go back to once the terminal is big enough again.
+    hidden_scene: Option<String>,
+}
 
-impl BBAppComponent for App {
-    fn register_to(
+impl App {
+    pub fn new(seed: Option<u64>, replaying: bool, mode: GameMode) -> Self {
+        Self {
+            seed,
+            replaying,
+            mode,
+            level_start_score: 0,
+            hidden_scene: None,
+        }
+    }
+
+    pub fn register_to(
+        self,
         builder: &mut anathema::runtime::Builder<()>,
     ) -> Result<(), anathema::runtime::Error> {
-        builder.component("App", "templates/app.aml", Self, AppState::default())?;
+        let mut state = AppState::default();
+
+        state.replaying.set(self.replaying);
+        state.survival.set(self.mode == GameMode::Survival);
+
+        if self.replaying {
+            self.start_game(&mut state);
+            state.scene.set(CurrentGameScene::Game.into());
+        }
+
+        builder.component("App", "templates/app.aml", self, state)?;
 
         Ok(())
     }
+
+    fn fit_viewport(&mut self, state: &mut AppState, context: &Context<'_, '_, AppState>) {
+        let viewport = context.viewport.size();
+        let width = viewport.width;
+        let height = viewport.height;
+        let too_small = width < MIN_WIDTH || height < MIN_HEIGHT;
+
+        state.width.set(width);
+        state.height.set(height);
+        state.too_small.set(too_small);
+
+        // the game pauses behind its own message and the end scene would ask
+        // for a name again if it was remounted, every other scene is swapped
+        // out for the message
+        let scene = state.scene.to_ref().clone();
+        let swappable = !matches!(scene.as_str(), "game" | "end" | "too_small");
+
+        if too_small && swappable {
+            self.hidden_scene = Some(scene);
+            state.scene.set(CurrentGameScene::TooSmall.into());
+        } else if !too_small && let Some(scene) = self.hidden_scene.take() {
+            state.scene.set(scene);
+        }
+    }
+
+    fn start_game(&self, state: &mut AppState) {
+        let game_number = *state.game_number.to_ref();
+        let seed = self
+            .seed
+            .unwrap_or_else(|| rand::random_range(0..=MAX_SEED));
+
+        state.level.set(1);
+        state.score.set(0);
+        state.seed.set(seed);
+        state.game_number.set(game_number + 1);
+    }
 }
 
 impl Component for App {
@@ -30,23 +111,42 @@ impl Component for App {
         event: &mut anathema::component::UserEvent<'_>,
         state: &mut Self::State,
         mut _children: anathema::component::Children<'_, '_>,
-        mut _context: anathema::component::Context<'_, '_, Self::State>,
+        mut context: anathema::component::Context<'_, '_, Self::State>,
     ) {
         if event.name() == "change_scene" {
             let Some(game_scene) = event.data_checked::<CurrentGameScene>() else {
                 return;
             };
 
+            if matches!(game_scene, CurrentGameScene::Game) {
+                self.start_game(state);
+                self.level_start_score = 0;
+            }
+
             state.scene.set(game_scene.into());
         } else if event.name() == "scored" {
             let points = event.data_checked::<u32>().copied().unwrap_or_default();
             let score = *state.score.to_ref();
 
             state.score.set(score + points);
-        } else if event.name() == "automation_mode_toggle" {
-            let automation_mode = *state.automation_mode.to_ref();
-
-            state.automation_mode.set(!automation_mode);
+        } else if event.name() == "level_cleared" {
+            let level = event.data_checked::<u8>().copied().unwrap_or_default();
+
+            self.level_start_score = *state.score.to_ref();
+            state.level.set(level.saturating_add(1));
+        } else if event.name() == "restart_level" {
+            state.score.set(self.level_start_score);
+        } else if event.name() == "quit_game" {
+            context.stop_runtime();
+        } else if event.name() == "cycle_controller" {
+            let controller = ControllerKind::from_index(*state.controller.to_ref() as usize).next();
+
+            state.controller.set(controller.index() as u8);
+            state.controller_name.set(controller.as_str().to_owned());
+            state.automation_mode.set(controller.is_automatic());
+        } else if event.name() == "toggle_aim_guide" {
+            let aim_guide = *state.aim_guide.to_ref();
+            state.aim_guide.set(!aim_guide);
         }
     }
 
@@ -56,12 +156,16 @@ impl Component for App {
         mut _children: anathema::component::Children<'_, '_>,
         context: anathema::component::Context<'_, '_, Self::State>,
     ) {
-        let viewport = context.viewport.size();
-        let width = viewport.width;
-        let height = viewport.height;
+        self.fit_viewport(state, &context);
+    }
 
-        state.width.set(width);
-        state.height.set(height);
+    fn on_resize(
+        &mut self,
+        state: &mut Self::State,
+        mut _children: anathema::component::Children<'_, '_>,
+        context: anathema::component::Context<'_, '_, Self::State>,
+    ) {
+        self.fit_viewport(state, &context);
     }
 }
 
@@ -71,6 +175,9 @@ pub enum CurrentGameScene {
     Splash,
     Game,
     End,
+    HighScores,
+    /// Shown instead of the other scenes while the terminal is too small.
+    TooSmall,
 }
 
 impl From<&CurrentGameScene> for String {
@@ -79,6 +186,8 @@ impl From<&CurrentGameScene> for String {
             CurrentGameScene::Splash => "splash",
             CurrentGameScene::Game => "game",
             CurrentGameScene::End => "end",
+            CurrentGameScene::HighScores => "high_scores",
+            CurrentGameScene::TooSmall => "too_small",
         }
         .to_owned()
     }
@@ -97,7 +206,21 @@ pub struct AppState {
     level: Value<u8>,
     width: Value<u16>,
     height: Value<u16>,
+    /// The paddle plays itself, true for every controller but the player's.
     automation_mode: Value<bool>,
+    /// Place of the paddle's controller in the cycle.
+    controller: Value<u8>,
+    controller_name: Value<String>,
+    /// Show where the ball is headed while the player has the paddle.
+    aim_guide: Value<bool>,
+    seed: Value<u64>,
+    game_number: Value<u32>,
+    /// Watching a replay, which doesn't go on the high score table.
+    replaying: Value<bool>,
+    /// Playing survival mode rather than the classic game.
+    survival: Value<bool>,
+    /// The terminal is below the minimum size.
+    too_small: Value<bool>,
 }
 
 impl Default for AppState {
@@ -105,10 +228,18 @@ impl Default for AppState {
         Self {
             scene: Value::new(CurrentGameScene::Splash.into()),
             score: Value::default(),
-            level: Value::default(),
+            level: Value::new(1),
             width: Value::default(),
             height: Value::default(),
             automation_mode: Value::default(),
+            controller: Value::default(),
+            controller_name: Value::new(ControllerKind::default().as_str().to_owned()),
+            aim_guide: Value::default(),
+            seed: Value::default(),
+            game_number: Value::default(),
+            replaying: Value::default(),
+            survival: Value::default(),
+            too_small: Value::default(),
         }
     }
 }
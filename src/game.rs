@@ -1,30 +1,93 @@
+pub mod bench;
+mod collision;
+pub mod controller;
 mod entity;
+pub mod level;
+mod powerup;
+mod render;
+pub mod replay;
+mod scoring;
+pub mod simulation;
+mod timestep;
+pub mod trajectory;
 mod vector;
 
-use std::ops::Rem;
-
-use crate::game::{entity::Entity, vector::Vector};
+use crate::game::{
+    controller::ControllerKind,
+    level::LevelLayout,
+    powerup::PowerUpKind,
+    replay::{Playback, Replay, ReplayAction},
+    scoring::{FloatingText, level_clear_bonus},
+    simulation::{Advance, GameEvent, Input, Simulation, Timing},
+    timestep::FixedTimestep,
+    vector::Vector,
+};
 use anathema::{
-    component::Component,
+    component::{Component, Context, KeyCode},
     default_widgets::Canvas,
-    state::{Color, State, Value},
+    state::{State, Value},
 };
-use bb_anathema_components::BBAppComponent;
-use rand::{Rng, rngs::ThreadRng};
 
-#[derive(Debug, Default)]
-pub struct Game(GameEntities);
+#[derive(Debug)]
+pub struct Game {
+    simulation: Simulation,
+    /// The timing new games are played with, replays bring their own.
+    timing: Timing,
+    /// Survival mode for new games, replays bring their own.
+    advance: Option<Advance>,
+    timestep: FixedTimestep,
+    input: Input,
+    game_number: u32,
+    /// Bumped by the game scene every time the level is restarted.
+    restart_number: u32,
+    /// Ticks played since the game started.
+    tick: u64,
+    recording: Replay,
+    /// A replay waiting to be played as the first game.
+    pending_replay: Option<Replay>,
+    playback: Option<Playback>,
+    /// The power-up timers last sent to the header.
+    power_up_status: String,
+    /// Recent scores drifting up from where they were made.
+    floating_texts: Vec<FloatingText>,
+    /// Playing itself behind the title, nothing it does counts.
+    demo: bool,
+}
+
+/// Aims for the bricks, which makes for a livelier demo than just keeping
+/// the ball in play.
+const DEMO_CONTROLLER: ControllerKind = ControllerKind::Targeting;
+
+impl Game {
+    pub fn new(
+        levels: Vec<LevelLayout>,
+        timing: Timing,
+        advance: Option<Advance>,
+        replay: Option<Replay>,
+    ) -> Self {
+        Self {
+            simulation: Simulation::new(Vector::zero(), levels, 0, timing),
+            timing,
+            advance,
+            timestep: FixedTimestep::new(timing.tick_rate),
+            input: Input::default(),
+            game_number: 0,
+            restart_number: 0,
+            tick: 0,
+            recording: Replay::new(0, Vector::zero(), timing, advance),
+            pending_replay: replay,
+            playback: None,
+            power_up_status: String::new(),
+            floating_texts: vec![],
+            demo: false,
+        }
+    }
 
-impl BBAppComponent for Game {
-    fn register_to(
+    pub fn register_to(
+        self,
         builder: &mut anathema::runtime::Builder<()>,
     ) -> Result<(), anathema::runtime::Error> {
-        builder.component(
-            "game",
-            "templates/game.aml",
-            Self::default(),
-            GameState::default(),
-        )?;
+        builder.component("game", "templates/game.aml", self, GameState::default())?;
 
         Ok(())
     }
@@ -32,9 +95,172 @@ impl BBAppComponent for Game {
 
 #[derive(State, Debug, Default)]
 pub struct GameState {
-    game_width: Value<i32>,
-    game_height: Value<i32>,
     playing: Value<bool>,
+    level: Value<u8>,
+    level_cleared: Value<bool>,
+    /// Name of the level that was just cleared.
+    level_name: Value<String>,
+    /// Points awarded for the lives and time left when the level was cleared.
+    level_bonus: Value<u32>,
+    replaying: Value<bool>,
+    replay_status: Value<String>,
+    /// A ball is resting on the paddle waiting to be launched.
+    ball_held: Value<bool>,
+}
+
+impl Game {
+    fn begin(&mut self, level: u8, state: &mut GameState) {
+        self.simulation.reset(level);
+        self.floating_texts.clear();
+        state.level.set(level);
+        state.level_cleared.set(false);
+        state.playing.set(true);
+    }
+
+    fn restart(&mut self, level: u8, state: &mut GameState) {
+        self.simulation.restart(level);
+        self.floating_texts.clear();
+        state.level.set(level);
+        state.level_cleared.set(false);
+        state.playing.set(true);
+    }
+
+    /// Keep the game that just ended as a replay, unless it was a replay or
+    /// a demo to begin with.
+    fn save_recording(&self) {
+        if self.playback.is_none() && !self.demo && !self.recording.is_empty() {
+            // losing a replay shouldn't get in the way of the game ending
+            let _ = self.recording.save();
+        }
+    }
+
+    /// Start a demo game on the first level with a random seed, leaving the
+    /// game number alone so the next real game still starts from scratch.
+    fn start_demo(&mut self, size: Vector, state: &mut GameState) {
+        let seed = rand::random();
+
+        self.demo = true;
+        self.restart_number = 0;
+        self.tick = 0;
+        self.playback = None;
+        self.simulation.new_game(seed);
+        self.simulation.set_size(size);
+        self.simulation.set_timing(self.timing);
+        self.simulation.set_advance(None);
+        self.recording = Replay::new(seed, self.simulation.size(), self.timing, None);
+        self.timestep = FixedTimestep::new(self.timing.tick_rate);
+        state.replaying.set(false);
+        self.begin(1, state);
+    }
+
+    /// Advance the game by a single tick, taking input either from the
+    /// player or from the replay being watched.
+    fn play_tick(
+        &mut self,
+        controller: ControllerKind,
+        state: &mut GameState,
+        context: &mut Context<'_, '_, GameState>,
+    ) {
+        let recorded_actions = self
+            .playback
+            .as_mut()
+            .map(|playback| playback.actions_at(self.tick));
+
+        let input = match recorded_actions {
+            Some(actions) => {
+                let mut input = Input::default();
+
+                for action in actions {
+                    match action {
+                        ReplayAction::Begin(level) => self.begin(level, state),
+                        ReplayAction::Restart(level) => {
+                            self.restart(level, state);
+                            // the scene puts the lives and score back the way
+                            // it does when the level is restarted from the menu
+                            context.publish("restart_level", ());
+                        }
+                        ReplayAction::Resize(size) => self.simulation.resize(size),
+                        ReplayAction::Input(recorded) => input = recorded,
+                        ReplayAction::Controller(kind) => {
+                            self.simulation.set_controller(kind.build())
+                        }
+                    }
+                }
+
+                input
+            }
+            None => {
+                if self.simulation.controller_name() != controller.as_str() {
+                    self.simulation.set_controller(controller.build());
+                    self.recording
+                        .record(self.tick, ReplayAction::Controller(controller));
+                }
+
+                let input = std::mem::take(&mut self.input);
+                self.recording.record(self.tick, ReplayAction::Input(input));
+
+                input
+            }
+        };
+
+        let step_seconds = self.simulation.timing().step_seconds();
+
+        for text in self.floating_texts.iter_mut() {
+            text.update(step_seconds);
+        }
+
+        self.floating_texts.retain(|text| !text.is_finished());
+
+        for event in self.simulation.step(input) {
+            match event {
+                GameEvent::Scored(score) => {
+                    if !self.simulation.automation_mode() {
+                        context.publish("scored", score.points as u32);
+                        self.floating_texts
+                            .push(FloatingText::new(score.label(), score.position));
+                    }
+                }
+                GameEvent::LostLife => {
+                    let lives = context
+                        .attribute("lives")
+                        .and_then(|v| v.to_int())
+                        .unwrap_or_default();
+
+                    // the game scene ends the game when a life is lost with none to spare
+                    if lives == 0 {
+                        self.save_recording();
+                    }
+
+                    context.publish("lost_life", ());
+                }
+                GameEvent::LevelCleared(level) => {
+                    let lives = context
+                        .attribute("lives")
+                        .and_then(|v| v.to_int())
+                        .unwrap_or_default();
+                    let bonus =
+                        level_clear_bonus(lives.max(0) as usize, self.simulation.level_seconds());
+
+                    context.publish("scored", bonus as u32);
+                    state.level_bonus.set(bonus as u32);
+                    state
+                        .level_name
+                        .set(self.simulation.level_name().to_owned());
+                    state.level_cleared.set(true);
+                    context.publish("level_cleared", level);
+                }
+                GameEvent::PowerUp(PowerUpKind::ExtraLife) => context.publish("extra_life", ()),
+                GameEvent::PowerUp(_) | GameEvent::PaddleHit | GameEvent::BallLost => (),
+                GameEvent::Overrun => {
+                    self.save_recording();
+
+                    context.publish("overrun", ());
+                }
+            }
+        }
+
+        self.tick += 1;
+    }
 }
 
 impl Component for Game {
@@ -47,118 +273,150 @@ impl Component for Game {
         state: &mut Self::State,
         mut children: anathema::component::Children<'_, '_>,
         mut context: anathema::component::Context<'_, '_, Self::State>,
-        _dt: std::time::Duration,
+        dt: std::time::Duration,
     ) {
-        let game_width = *state.game_width.to_ref();
-        let game_height = *state.game_height.to_ref();
-        let game_size = Vector::new(game_width, game_height);
-        let automation_mode = context
-            .attribute("automation_mode")
+        // the header picks the controller by its place in the cycle
+        let controller = if self.demo {
+            DEMO_CONTROLLER
+        } else {
+            context
+                .attribute("controller")
+                .and_then(|v| v.to_int())
+                .map(|index| ControllerKind::from_index(index as usize))
+                .unwrap_or_default()
+        };
+
+        // a field too small to play on is paused until the terminal grows
+        let too_small = context
+            .attribute("too_small")
             .and_then(|v| v.as_bool())
             .unwrap_or_default();
+        let paused = too_small
+            || context
+                .attribute("paused")
+                .and_then(|v| v.as_bool())
+                .unwrap_or_default();
+        let restart_number = context
+            .attribute("restart_number")
+            .and_then(|v| v.to_int())
+            .unwrap_or_default() as u32;
+
+        if restart_number != self.restart_number {
+            self.restart_number = restart_number;
+
+            if self.playback.is_none() {
+                let level = context
+                    .attribute("level")
+                    .and_then(|v| v.to_int())
+                    .unwrap_or(1) as u8;
+
+                self.recording
+                    .record(self.tick, ReplayAction::Restart(level));
+                self.restart(level, state);
+            }
+        }
 
-        children.elements().by_tag("canvas").first(|el, _| {
-            let canvas = el.to::<Canvas>();
-
-            canvas.clear();
-
-            let Some(ball) = &mut self.0.ball else { return };
-            let Some(paddle) = &mut self.0.paddle else {
-                return;
-            };
-
-            if automation_mode {
-                let mut simulated_ball = *ball;
-                while simulated_ball.position.y < paddle.position.y {
-                    simulated_ball.update(game_size);
-                }
+        if !too_small && self.playback.is_none() {
+            let width = context
+                .attribute("width")
+                .and_then(|v| v.to_int())
+                .unwrap_or_default();
+            let height = context
+                .attribute("height")
+                .and_then(|v| v.to_int())
+                .unwrap_or_default();
+            let size = Vector::new(width as f32, height as f32);
+
+            if size != self.simulation.size() {
+                self.recording.record(self.tick, ReplayAction::Resize(size));
+                self.simulation.resize(size);
+            }
+        }
 
-                if paddle.position.x > simulated_ball.position.x {
-                    paddle.position.x -= 2;
-                } else if paddle.position.x + paddle.size.x <= simulated_ball.position.x {
-                    paddle.position.x += 2;
-                } else {
-                    let simulated_ball_offset =
-                        simulated_ball.position.x - (paddle.position.x + paddle.size.x / 2);
-                    if simulated_ball_offset.abs() < 1 {
-                        paddle.position.x += 1;
-                    }
-                }
+        // the simulation runs at its own rate however often frames are drawn,
+        // and time spent paused is never caught up on
+        let ticks = match &mut self.playback {
+            _ if paused => {
+                self.timestep.reset();
+                0
             }
+            Some(playback) if playback.take_step_request() => 1,
+            Some(playback) => self.timestep.advance(playback.scale_elapsed(dt)),
+            None => self.timestep.advance(dt),
+        };
 
-            ball.update(game_size);
-            paddle.update(game_size);
+        for _ in 0..ticks {
+            self.play_tick(controller, state, &mut context);
+        }
 
-            if paddle.is_point_inside(&ball.position) {
-                ball.position.y = paddle.position.y - 1;
-                ball.velocity.y *= -1;
-                // am I on the left, center, or right sides
-                let mut shifted_ball =
-                    (ball.position.x - (paddle.position.x + (paddle.size.x / 2))).clamp(-3, 3);
+        if let Some(playback) = &self.playback {
+            let status = if playback.is_finished() {
+                "replay finished".to_owned()
+            } else if playback.paused {
+                "replay paused".to_owned()
+            } else {
+                format!("replay {}x", playback.speed)
+            };
 
-                if automation_mode {
-                    shifted_ball = self.0.rng.random_range(shifted_ball - 1..shifted_ball + 1);
-                }
+            state
+                .replay_status
+                .set(format!("{status} | space: pause  n: step  1/2/4: speed"));
+        }
 
-                ball.velocity.x = shifted_ball;
-            }
+        state.playing.set(self.simulation.is_playing());
+        state.ball_held.set(self.simulation.is_ball_held());
 
-            for brick in self.0.bricks.iter_mut() {
-                if brick.is_point_inside(&ball.position) {
-                    let previous_ball_position = ball.previous_location();
+        let power_up_status = self
+            .simulation
+            .effects()
+            .summary(self.simulation.timing().tick_rate);
 
-                    brick.lose_health();
+        if power_up_status != self.power_up_status {
+            context.publish("power_ups", power_up_status.clone());
+            self.power_up_status = power_up_status;
+        }
 
-                    if brick.health == 0 && !automation_mode {
-                        context.publish("scored", brick.value);
-                    }
+        // the guide helps the player aim, there's no one to help when the
+        // paddle plays itself
+        let aim_guide = context
+            .attribute("aim_guide")
+            .and_then(|v| v.as_bool())
+            .unwrap_or_default()
+            && !self.simulation.automation_mode();
+        let trajectory = aim_guide.then(|| self.simulation.trajectory()).flatten();
 
-                    if previous_ball_position.x < brick.position.x {
-                        ball.velocity.x *= -1;
-                        ball.position.x = brick.position.x - 1;
-                    } else if previous_ball_position.x > brick.position.x + brick.size.x - 1 {
-                        ball.velocity.x *= -1;
-                        ball.position.x = brick.position.x + brick.size.x
-                    }
+        children.elements().by_tag("canvas").first(|el, _| {
+            let canvas = el.to::<Canvas>();
+            let entities = self.simulation.entities();
 
-                    if previous_ball_position.y < brick.position.y {
-                        ball.position.y = brick.position.y - 1;
-                    } else if previous_ball_position.y > brick.position.y + brick.size.y - 1 {
-                        ball.position.y = brick.position.y + brick.size.y;
-                    }
+            canvas.clear();
 
-                    ball.velocity.y *= -1;
-                }
+            if let Some(trajectory) = &trajectory {
+                trajectory.draw(canvas);
             }
 
-            if ball.position.y > game_size.y {
-                ball.is_alive = false;
+            for ball in entities.balls.iter() {
+                ball.draw(canvas);
             }
 
-            ball.draw(canvas);
-            paddle.draw(canvas);
+            if let Some(paddle) = &entities.paddle {
+                paddle.draw(canvas);
+            }
 
-            for brick in self.0.bricks.iter() {
+            for brick in entities.bricks.iter() {
                 brick.draw(canvas);
             }
 
-            self.0.bricks.retain(|brick| brick.health > 0);
-
-            if self.0.bricks.is_empty() && ball.is_alive {
-                state.playing.set(false);
-                self.0.ball = None;
+            for capsule in entities.capsules.iter() {
+                capsule.draw(canvas);
+            }
 
-                if automation_mode {
-                    reset_game(&mut self.0, state);
-                }
-            } else if !ball.is_alive {
-                self.0.ball = None;
-                context.publish("lost_life", ());
-                state.playing.set(false);
+            for shot in entities.projectiles.iter() {
+                shot.draw(canvas);
+            }
 
-                if automation_mode {
-                    reset_game(&mut self.0, state);
-                }
+            for text in self.floating_texts.iter() {
+                text.draw(canvas);
             }
         });
     }
@@ -179,9 +437,64 @@ impl Component for Game {
             .expect("don't have a height")
             .to_int()
             .expect("height isn't a number");
+        let game_number = context
+            .attribute("game_number")
+            .and_then(|v| v.to_int())
+            .unwrap_or_default() as u32;
+
+        let demo = context
+            .attribute("demo")
+            .and_then(|v| v.as_bool())
+            .unwrap_or_default();
+
+        if demo {
+            self.start_demo(Vector::new(width as f32, height as f32), state);
+            return;
+        }
+
+        // the component is remounted whenever automation is toggled, only
+        // a new game number means a new game, or coming back from a demo
+        if game_number != self.game_number || self.demo {
+            self.demo = false;
+            let seed = context
+                .attribute("seed")
+                .and_then(|v| v.to_int())
+                .unwrap_or_default() as u64;
+
+            self.game_number = game_number;
+            self.restart_number = 0;
+            self.tick = 0;
+            self.floating_texts.clear();
+            self.playback = self.pending_replay.take().map(Playback::new);
+
+            let (timing, advance) = match &self.playback {
+                Some(playback) => {
+                    let replay = playback.replay();
+                    self.simulation.new_game(replay.seed);
+                    self.simulation.set_size(replay.size);
+                    (replay.timing, replay.advance)
+                }
+                None => {
+                    let size = Vector::new(width as f32, height as f32);
+                    self.simulation.new_game(seed);
+                    self.simulation.set_size(size);
+                    self.recording =
+                        Replay::new(seed, self.simulation.size(), self.timing, self.advance);
+                    (self.timing, self.advance)
+                }
+            };
 
-        state.game_width.set(width as i32);
-        state.game_height.set(height as i32);
+            self.simulation.set_timing(timing);
+            self.simulation.set_advance(advance);
+            self.timestep = FixedTimestep::new(timing.tick_rate);
+
+            state.playing.set(false);
+            state.level_cleared.set(false);
+            state.replaying.set(self.playback.is_some());
+        } else {
+            // time spent unmounted shouldn't be caught up on
+            self.timestep.reset();
+        }
     }
 
     fn on_event(
@@ -189,10 +502,16 @@ impl Component for Game {
         event: &mut anathema::component::UserEvent<'_>,
         state: &mut Self::State,
         mut _children: anathema::component::Children<'_, '_>,
-        mut _context: anathema::component::Context<'_, '_, Self::State>,
+        context: anathema::component::Context<'_, '_, Self::State>,
     ) {
-        if event.name() == "begin" {
-            reset_game(&mut self.0, state);
+        if event.name() == "begin" && self.playback.is_none() {
+            let level = context
+                .attribute("level")
+                .and_then(|v| v.to_int())
+                .unwrap_or(1) as u8;
+
+            self.recording.record(self.tick, ReplayAction::Begin(level));
+            self.begin(level, state);
         }
     }
 
@@ -201,41 +520,50 @@ impl Component for Game {
         key: anathema::component::KeyEvent,
         _state: &mut Self::State,
         mut _children: anathema::component::Children<'_, '_>,
-        context: anathema::component::Context<'_, '_, Self::State>,
+        mut context: anathema::component::Context<'_, '_, Self::State>,
     ) {
+        // like an arcade cabinet, any key brings the menu back
+        if self.demo {
+            context.publish("end_demo", ());
+            return;
+        }
+
+        if matches!(key.code, KeyCode::Esc | KeyCode::Char('p')) {
+            context.publish("toggle_pause", ());
+            return;
+        }
+
+        if matches!(key.code, KeyCode::Char('q')) {
+            context.publish("quit_game", ());
+            return;
+        }
+
+        if matches!(key.code, KeyCode::Char('g')) {
+            context.publish("toggle_aim_guide", ());
+            return;
+        }
+
         if context
-            .attribute("automation_mode")
+            .attribute("paused")
             .and_then(|v| v.as_bool())
             .unwrap_or_default()
         {
             return;
         }
 
-        let Some(paddle) = &mut self.0.paddle else {
-            return;
-        };
-        let move_speed = 1;
+        if let Some(playback) = &mut self.playback {
+            match key.code {
+                KeyCode::Char(' ') => playback.paused = !playback.paused,
+                KeyCode::Char('n') => playback.request_step(),
+                KeyCode::Char('1') => playback.speed = 1,
+                KeyCode::Char('2') => playback.speed = 2,
+                KeyCode::Char('4') => playback.speed = 4,
+                _ => (),
+            }
 
-        if matches!(key.code, anathema::component::KeyCode::Left) {
-            let force = Vector::new(-move_speed, 0);
-            paddle.apply_force(force);
-        } else if matches!(key.code, anathema::component::KeyCode::Right) {
-            let force = Vector::new(move_speed, 0);
-            paddle.apply_force(force);
+            return;
         }
-    }
 
-    fn on_mouse(
-        &mut self,
-        mouse: anathema::component::MouseEvent,
-        _state: &mut Self::State,
-        mut _children: anathema::component::Children<'_, '_>,
-        context: anathema::component::Context<'_, '_, Self::State>,
-    ) {
-        let Some(paddle) = &mut self.0.paddle else {
-            return;
-        };
-        let mouse_position = mouse.pos();
         if context
             .attribute("automation_mode")
             .and_then(|v| v.as_bool())
@@ -244,88 +572,50 @@ impl Component for Game {
             return;
         }
 
-        paddle.velocity.x = 0;
-        paddle.position.x = mouse_position.x - paddle.size.x / 2;
-    }
-}
-
-#[derive(Debug, Default)]
-pub struct GameEntities {
-    ball: Option<Entity>,
-    paddle: Option<Entity>,
-    bricks: Vec<Entity>,
-    rng: ThreadRng,
-}
+        let move_speed = 1;
 
-fn reset_game(game_entities: &mut GameEntities, state: &mut GameState) {
-    let game_width = *state.game_width.to_ref();
-    let game_height = *state.game_height.to_ref();
-    let ball_position = Vector::new(game_width / 2, game_height / 2);
-    let ball_velocity = Vector::new(0, 1);
-    let ball_size = Vector::new(1, 1);
-    let mut ball = Entity::new(
-        ball_position,
-        ball_size,
-        '*',
-        anathema::state::Color::Reset,
-        1,
-    );
-    ball.apply_force(ball_velocity);
-    game_entities.ball = Some(ball);
-
-    let paddle_size = Vector::new(10, 2);
-    let paddle_position = Vector::new(
-        game_width / 2 - paddle_size.x / 2,
-        game_height - paddle_size.y,
-    );
-    let paddle = Entity::new(
-        paddle_position,
-        paddle_size,
-        '=',
-        anathema::state::Color::Reset,
-        1,
-    );
-    game_entities.paddle = Some(paddle);
-
-    if game_entities.bricks.is_empty() {
-        let brick_size = Vector::new(calculate_brick_size(game_width), 1);
-        let bricks_per_row = game_width / brick_size.x;
-        let brick_character = ' ';
-        for count in 0..bricks_per_row {
-            let position = Vector::new(count * brick_size.x, 0);
-            let health = 1;
-            let color = Color::Red;
-            let brick = Entity::new(position, brick_size, brick_character, color, health);
-            game_entities.bricks.push(brick);
+        if matches!(key.code, KeyCode::Left) {
+            self.input.paddle_force -= move_speed;
+        } else if matches!(key.code, KeyCode::Right) {
+            self.input.paddle_force += move_speed;
+        } else if matches!(key.code, KeyCode::Char(' ')) {
+            self.input.action = true;
         }
+    }
 
-        for count in 0..bricks_per_row {
-            let position = Vector::new(count * brick_size.x, brick_size.y);
-            let health = 1;
-            let color = Color::Magenta;
-            let brick = Entity::new(position, brick_size, brick_character, color, health);
-            game_entities.bricks.push(brick);
-        }
+    fn on_mouse(
+        &mut self,
+        mouse: anathema::component::MouseEvent,
+        _state: &mut Self::State,
+        mut _children: anathema::component::Children<'_, '_>,
+        mut context: anathema::component::Context<'_, '_, Self::State>,
+    ) {
+        if self.demo {
+            if mouse.lsb_down() {
+                context.publish("end_demo", ());
+            }
 
-        for count in 0..bricks_per_row {
-            let position = Vector::new(count * brick_size.x, brick_size.y + 1);
-            let health = 3;
-            let color = Color::Cyan;
-            let brick = Entity::new(position, brick_size, brick_character, color, health);
-            game_entities.bricks.push(brick);
+            return;
         }
-    }
 
-    state.playing.set(true);
-}
+        let mouse_position = mouse.pos();
+        if self.playback.is_some()
+            || context
+                .attribute("paused")
+                .and_then(|v| v.as_bool())
+                .unwrap_or_default()
+            || context
+                .attribute("automation_mode")
+                .and_then(|v| v.as_bool())
+                .unwrap_or_default()
+        {
+            return;
+        }
 
-fn calculate_brick_size(game_width: i32) -> i32 {
-    let mut brick_width = 12;
+        self.input.paddle_target = Some(mouse_position.x);
 
-    loop {
-        if game_width.rem(brick_width) == 0 {
-            return brick_width;
+        if mouse.lsb_down() {
+            self.input.action = true;
         }
-        brick_width -= 1;
     }
 }
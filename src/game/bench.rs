@@ -0,0 +1,299 @@
+use crate::game::{
+    controller::ControllerKind,
+    level::LevelLayout,
+    powerup::PowerUpKind,
+    simulation::{Advance, GameEvent, Input, MAX_LIVES, STARTING_LIVES, Simulation, Timing},
+    vector::Vector,
+};
+
+/// The field of an 80 by 24 terminal once the header is taken off.
+const FIELD_SIZE: Vector = Vector { x: 80.0, y: 22.0 };
+/// A game ends after this long even with lives to spare, so a controller
+/// that never misses still finishes.
+const MAX_GAME_MINUTES: u64 = 30;
+
+/// A controller playing whole games on its own, as fast as they can be
+/// stepped, to see how well it plays.
+#[derive(Debug, Clone, Copy)]
+pub struct Bench {
+    pub controller: ControllerKind,
+    pub timing: Timing,
+    pub advance: Option<Advance>,
+    /// Seed of the first game, every game after it uses the next one.
+    pub seed: u64,
+}
+
+impl Bench {
+    pub fn run(&self, levels: &[LevelLayout], games: u32) -> BenchReport {
+        let games = (0..games as u64)
+            .map(|game| self.play(levels, self.seed.wrapping_add(game)))
+            .collect();
+
+        BenchReport {
+            controller: self.controller,
+            timing: self.timing,
+            games,
+        }
+    }
+
+    /// Play a single game from the first level until the last life is lost,
+    /// keeping lives and levels the way the game scenes do.
+    fn play(&self, levels: &[LevelLayout], seed: u64) -> GameStats {
+        let mut simulation = Simulation::new(FIELD_SIZE, levels.to_vec(), seed, self.timing);
+
+        simulation.set_advance(self.advance);
+        simulation.set_controller(self.controller.build());
+        simulation.set_endless(false);
+        simulation.reset(1);
+
+        let mut stats = GameStats {
+            seed,
+            ..GameStats::default()
+        };
+        let mut lives = STARTING_LIVES;
+        let mut rally = 0;
+        let mut level_ticks = 0;
+        let max_ticks = MAX_GAME_MINUTES * 60 * self.timing.tick_rate as u64;
+
+        while stats.ticks < max_ticks {
+            let bricks_before = simulation.entities().bricks.len();
+            let events = simulation.step(Input::default());
+
+            stats.ticks += 1;
+            level_ticks += 1;
+
+            // an overrun sweeps the bricks away without anyone breaking them
+            if events.contains(&GameEvent::Overrun) {
+                break;
+            }
+
+            let bricks_after = simulation.entities().bricks.len();
+            stats.bricks_destroyed += bricks_before.saturating_sub(bricks_after) as u32;
+
+            for event in events {
+                match event {
+                    GameEvent::PaddleHit => {
+                        rally += 1;
+                        stats.longest_rally = stats.longest_rally.max(rally);
+                    }
+                    GameEvent::BallLost => {
+                        stats.paddle_misses += 1;
+                        rally = 0;
+                    }
+                    GameEvent::LostLife => {
+                        stats.lives_lost += 1;
+
+                        if lives == 0 {
+                            return stats;
+                        }
+
+                        lives -= 1;
+                        simulation.reset(simulation.level());
+                    }
+                    GameEvent::LevelCleared(level) => {
+                        stats.clear_ticks.push(level_ticks);
+                        level_ticks = 0;
+                        simulation.reset(level.saturating_add(1));
+                    }
+                    GameEvent::PowerUp(PowerUpKind::ExtraLife) => {
+                        lives = lives.saturating_add(1).min(MAX_LIVES);
+                    }
+                    GameEvent::Scored(_) | GameEvent::PowerUp(_) | GameEvent::Overrun => (),
+                }
+            }
+        }
+
+        stats
+    }
+}
+
+/// How a single benchmarked game went.
+#[derive(Debug, Default, Clone)]
+pub struct GameStats {
+    pub seed: u64,
+    /// Steps the game lasted.
+    pub ticks: u64,
+    /// Steps taken to clear each level, in the order they were cleared.
+    pub clear_ticks: Vec<u64>,
+    pub lives_lost: u32,
+    /// Balls that fell past the paddle, counting those lost while another
+    /// ball was still in play.
+    pub paddle_misses: u32,
+    pub bricks_destroyed: u32,
+    /// Most times in a row a ball came back off the paddle before one was
+    /// missed.
+    pub longest_rally: u32,
+}
+
+impl GameStats {
+    fn mean_ticks_to_clear(&self) -> Option<f32> {
+        mean_ticks(&self.clear_ticks)
+    }
+
+    fn bricks_per_minute(&self, timing: Timing) -> f32 {
+        bricks_per_minute(self.bricks_destroyed, self.ticks, timing)
+    }
+}
+
+/// Every game played by a bench, ready to be printed.
+#[derive(Debug, Clone)]
+pub struct BenchReport {
+    pub controller: ControllerKind,
+    pub timing: Timing,
+    pub games: Vec<GameStats>,
+}
+
+impl BenchReport {
+    /// Mean over every level cleared in every game, rather than a mean of
+    /// each game's mean, so short games don't count for more.
+    fn mean_ticks_to_clear(&self) -> Option<f32> {
+        let clear_ticks: Vec<u64> = self
+            .games
+            .iter()
+            .flat_map(|game| game.clear_ticks.iter().copied())
+            .collect();
+
+        mean_ticks(&clear_ticks)
+    }
+
+    fn mean_lives_lost(&self) -> f32 {
+        self.mean_of(|game| game.lives_lost as f32)
+    }
+
+    fn mean_paddle_misses(&self) -> f32 {
+        self.mean_of(|game| game.paddle_misses as f32)
+    }
+
+    fn bricks_per_minute(&self) -> f32 {
+        let bricks = self.games.iter().map(|game| game.bricks_destroyed).sum();
+        let ticks = self.games.iter().map(|game| game.ticks).sum();
+
+        bricks_per_minute(bricks, ticks, self.timing)
+    }
+
+    fn longest_rally(&self) -> u32 {
+        self.games
+            .iter()
+            .map(|game| game.longest_rally)
+            .max()
+            .unwrap_or_default()
+    }
+
+    fn mean_of(&self, value: impl Fn(&GameStats) -> f32) -> f32 {
+        let total: f32 = self.games.iter().map(value).sum();
+
+        total / self.games.len().max(1) as f32
+    }
+
+    /// A row for every game followed by one for all of them together.
+    pub fn table(&self) -> String {
+        let mut table = format!(
+            "{} over {} games at {} ticks per second\n\n",
+            self.controller.as_str(),
+            self.games.len(),
+            self.timing.tick_rate
+        );
+
+        table.push_str(&format!(
+            "{:>6} {:>8} {:>14} {:>10} {:>7} {:>10} {:>13}\n",
+            "seed",
+            "cleared",
+            "ticks to clear",
+            "lives lost",
+            "misses",
+            "bricks/min",
+            "longest rally"
+        ));
+
+        for game in &self.games {
+            table.push_str(&format!(
+                "{:>6} {:>8} {:>14} {:>10} {:>7} {:>10.1} {:>13}\n",
+                game.seed,
+                game.clear_ticks.len(),
+                table_ticks(game.mean_ticks_to_clear()),
+                game.lives_lost,
+                game.paddle_misses,
+                game.bricks_per_minute(self.timing),
+                game.longest_rally
+            ));
+        }
+
+        let cleared: usize = self.games.iter().map(|game| game.clear_ticks.len()).sum();
+
+        table.push_str(&format!(
+            "{:>6} {:>8.1} {:>14} {:>10.1} {:>7.1} {:>10.1} {:>13}",
+            "mean",
+            cleared as f32 / self.games.len().max(1) as f32,
+            table_ticks(self.mean_ticks_to_clear()),
+            self.mean_lives_lost(),
+            self.mean_paddle_misses(),
+            self.bricks_per_minute(),
+            self.longest_rally()
+        ));
+
+        table
+    }
+
+    pub fn json(&self) -> String {
+        let games: Vec<String> = self
+            .games
+            .iter()
+            .map(|game| {
+                format!(
+                    "    {{\"seed\": {}, \"ticks\": {}, \"levels_cleared\": {}, \"mean_ticks_to_clear\": {}, \"lives_lost\": {}, \"paddle_misses\": {}, \"bricks_per_minute\": {}, \"longest_rally\": {}}}",
+                    game.seed,
+                    game.ticks,
+                    game.clear_ticks.len(),
+                    json_number(game.mean_ticks_to_clear()),
+                    game.lives_lost,
+                    game.paddle_misses,
+                    json_number(Some(game.bricks_per_minute(self.timing))),
+                    game.longest_rally
+                )
+            })
+            .collect();
+
+        format!(
+            "{{\n  \"controller\": \"{}\",\n  \"tick_rate\": {},\n  \"games\": [\n{}\n  ],\n  \"summary\": {{\"mean_ticks_to_clear\": {}, \"mean_lives_lost\": {}, \"mean_paddle_misses\": {}, \"bricks_per_minute\": {}, \"longest_rally\": {}}}\n}}",
+            self.controller.as_str(),
+            self.timing.tick_rate,
+            games.join(",\n"),
+            json_number(self.mean_ticks_to_clear()),
+            json_number(Some(self.mean_lives_lost())),
+            json_number(Some(self.mean_paddle_misses())),
+            json_number(Some(self.bricks_per_minute())),
+            self.longest_rally()
+        )
+    }
+}
+
+fn mean_ticks(ticks: &[u64]) -> Option<f32> {
+    if ticks.is_empty() {
+        return None;
+    }
+
+    Some(ticks.iter().sum::<u64>() as f32 / ticks.len() as f32)
+}
+
+fn bricks_per_minute(bricks: u32, ticks: u64, timing: Timing) -> f32 {
+    let minutes = ticks as f32 * timing.step_seconds() / 60.0;
+
+    if minutes > 0.0 {
+        bricks as f32 / minutes
+    } else {
+        0.0
+    }
+}
+
+/// Nothing to show when no level was cleared.
+fn table_ticks(ticks: Option<f32>) -> String {
+    ticks.map_or_else(|| "-".to_string(), |ticks| format!("{ticks:.1}"))
+}
+
+/// JSON has no NaN or infinity, so anything but a finite number is null.
+fn json_number(value: Option<f32>) -> String {
+    match value {
+        Some(value) if value.is_finite() => format!("{value:.2}"),
+        _ => "null".to_string(),
+    }
+}
@@ -0,0 +1,241 @@
+use crate::game::{entity::Entity, vector::Vector};
+
+/// Anything closer than this is treated as touching at the same moment.
+const EPSILON: f32 = 1e-4;
+/// How far to step back from a face after touching it.
+const BACK_OFF: f32 = 1e-3;
+
+/// Move a tiny distance back along `travel`, used to step out of a face
+/// after touching it.
+pub fn back_off(point: Vector, travel: Vector) -> Vector {
+    if travel.length() < EPSILON {
+        return point;
+    }
+
+    point - travel.with_length(BACK_OFF)
+}
+
+/// Which side of a rectangle was touched, deciding how the ball bounces.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Face {
+    /// The left or right side, the ball bounces horizontally.
+    Side,
+    /// The top or bottom, the ball bounces vertically.
+    TopOrBottom,
+    /// Both at once, the ball comes straight back.
+    Corner,
+    /// The path started inside the rectangle.
+    Inside,
+}
+
+impl Face {
+    pub fn flips_x(&self) -> bool {
+        matches!(self, Face::Side | Face::Corner)
+    }
+
+    pub fn flips_y(&self) -> bool {
+        matches!(self, Face::TopOrBottom | Face::Corner | Face::Inside)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Contact {
+    /// How far along the path the contact happens, from 0 to 1.
+    pub time: f32,
+    pub face: Face,
+}
+
+impl Contact {
+    pub fn is_simultaneous(&self, other: &Contact) -> bool {
+        (self.time - other.time).abs() < EPSILON
+    }
+}
+
+/// Find the first point where a point travelling from `origin` by `travel`
+/// touches `target`, if it does at all.
+pub fn sweep(origin: Vector, travel: Vector, target: &Entity) -> Option<Contact> {
+    let left = target.position.x;
+    let top = target.position.y;
+    let right = left + target.size.x;
+    let bottom = top + target.size.y;
+
+    let (x_entry, x_exit) = slab(origin.x, travel.x, left, right)?;
+    let (y_entry, y_exit) = slab(origin.y, travel.y, top, bottom)?;
+    let entry = x_entry.max(y_entry);
+    let exit = x_exit.min(y_exit);
+
+    if entry >= exit || entry > 1.0 || exit <= 0.0 {
+        return None;
+    }
+
+    if entry < 0.0 {
+        return Some(Contact {
+            time: 0.0,
+            face: Face::Inside,
+        });
+    }
+
+    let face = if (x_entry - y_entry).abs() < EPSILON {
+        Face::Corner
+    } else if x_entry > y_entry {
+        Face::Side
+    } else {
+        Face::TopOrBottom
+    };
+
+    Some(Contact { time: entry, face })
+}
+
+/// Find when a point travelling from `origin` by `travel` reaches the left,
+/// right or top edge of a field of `size` cells.
+pub fn sweep_walls(origin: Vector, travel: Vector, size: Vector) -> Option<Contact> {
+    let left = 0.5;
+    let right = size.x - 0.5;
+    let top = 0.5;
+
+    let x_time = if travel.x < -EPSILON {
+        (left - origin.x) / travel.x
+    } else if travel.x > EPSILON {
+        (right - origin.x) / travel.x
+    } else {
+        f32::INFINITY
+    };
+    let y_time = if travel.y < -EPSILON {
+        (top - origin.y) / travel.y
+    } else {
+        f32::INFINITY
+    };
+    let time = x_time.min(y_time);
+
+    if time > 1.0 {
+        return None;
+    }
+
+    let face = if (x_time - y_time).abs() < EPSILON {
+        Face::Corner
+    } else if x_time < y_time {
+        Face::Side
+    } else {
+        Face::TopOrBottom
+    };
+
+    Some(Contact {
+        time: time.max(0.0),
+        face,
+    })
+}
+
+/// The times a point enters and leaves the space between `min` and `max`
+/// along a single axis.
+fn slab(origin: f32, travel: f32, min: f32, max: f32) -> Option<(f32, f32)> {
+    // moving along an edge still touches, so a ball heading straight up a
+    // seam hits the bricks on both sides of it
+    if travel.abs() < EPSILON {
+        if origin >= min && origin <= max {
+            return Some((f32::NEG_INFINITY, f32::INFINITY));
+        }
+
+        return None;
+    }
+
+    let enter = (min - origin) / travel;
+    let leave = (max - origin) / travel;
+
+    Some((enter.min(leave), enter.max(leave)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::entity::EntityColor;
+
+    fn brick(x: f32, y: f32, width: f32, height: f32) -> Entity {
+        Entity::new(
+            Vector::new(x, y),
+            Vector::new(width, height),
+            ' ',
+            EntityColor::Red,
+            1,
+        )
+    }
+
+    #[test]
+    fn sweep_hits_the_side_facing_the_path() {
+        let target = brick(10.0, 0.0, 4.0, 2.0);
+
+        let contact = sweep(Vector::new(8.0, 1.0), Vector::new(4.0, 0.0), &target).unwrap();
+        assert_eq!(contact.face, Face::Side);
+        assert!((contact.time - 0.5).abs() < EPSILON);
+
+        let contact = sweep(Vector::new(12.0, 4.0), Vector::new(0.0, -4.0), &target).unwrap();
+        assert_eq!(contact.face, Face::TopOrBottom);
+        assert!((contact.time - 0.5).abs() < EPSILON);
+    }
+
+    #[test]
+    fn sweep_through_a_corner_hits_both_faces() {
+        let target = brick(10.0, 0.0, 4.0, 2.0);
+        let contact = sweep(Vector::new(8.0, 4.0), Vector::new(4.0, -4.0), &target).unwrap();
+
+        assert_eq!(contact.face, Face::Corner);
+        assert!(contact.face.flips_x() && contact.face.flips_y());
+    }
+
+    #[test]
+    fn sweep_starting_inside_is_a_contact_at_the_start() {
+        let target = brick(10.0, 0.0, 4.0, 2.0);
+        let contact = sweep(Vector::new(11.0, 1.0), Vector::new(4.0, 4.0), &target).unwrap();
+
+        assert_eq!(contact.face, Face::Inside);
+        assert_eq!(contact.time, 0.0);
+    }
+
+    #[test]
+    fn sweep_misses_what_is_out_of_reach_or_behind() {
+        let target = brick(10.0, 0.0, 4.0, 2.0);
+
+        // stops short
+        assert!(sweep(Vector::new(4.0, 1.0), Vector::new(4.0, 0.0), &target).is_none());
+        // heading away
+        assert!(sweep(Vector::new(8.0, 1.0), Vector::new(-4.0, 0.0), &target).is_none());
+        // passing underneath
+        assert!(sweep(Vector::new(8.0, 3.0), Vector::new(8.0, 0.0), &target).is_none());
+    }
+
+    #[test]
+    fn sweep_straight_along_a_seam_touches_both_bricks() {
+        let left = brick(0.0, 0.0, 4.0, 1.0);
+        let right = brick(4.0, 0.0, 4.0, 1.0);
+        let origin = Vector::new(4.0, 3.0);
+        let travel = Vector::new(0.0, -4.0);
+
+        let left_contact = sweep(origin, travel, &left).unwrap();
+        let right_contact = sweep(origin, travel, &right).unwrap();
+
+        assert!(left_contact.is_simultaneous(&right_contact));
+        assert_eq!(left_contact.face, Face::TopOrBottom);
+    }
+
+    #[test]
+    fn sweep_walls_picks_the_wall_reached_first() {
+        let size = Vector::new(20.0, 10.0);
+
+        let contact = sweep_walls(Vector::new(10.0, 5.0), Vector::new(-19.0, -1.0), size).unwrap();
+        assert_eq!(contact.face, Face::Side);
+        assert!((contact.time - 0.5).abs() < EPSILON);
+
+        let contact = sweep_walls(Vector::new(10.0, 5.0), Vector::new(1.0, -9.0), size).unwrap();
+        assert_eq!(contact.face, Face::TopOrBottom);
+
+        let contact = sweep_walls(Vector::new(10.0, 5.0), Vector::new(9.5, -4.5), size).unwrap();
+        assert_eq!(contact.face, Face::Corner);
+    }
+
+    #[test]
+    fn sweep_walls_has_no_floor() {
+        let size = Vector::new(20.0, 10.0);
+
+        assert!(sweep_walls(Vector::new(10.0, 5.0), Vector::new(0.0, 20.0), size).is_none());
+        assert!(sweep_walls(Vector::new(10.0, 5.0), Vector::new(2.0, 2.0), size).is_none());
+    }
+}
@@ -0,0 +1,475 @@
+use rand::Rng;
+use rand_chacha::ChaCha8Rng;
+
+use crate::game::{
+    entity::Entity,
+    simulation::{Input, MAX_DEFLECTION, deflection},
+    trajectory::Trajectory,
+    vector::Vector,
+};
+
+/// How far around a brick, in cells, other bricks count towards its cluster.
+const CLUSTER_REACH: Vector = Vector { x: 8.0, y: 2.0 };
+/// Most the game moves where the ball touches the paddle while an AI plays,
+/// so a ball can't bounce along the same path forever.
+const AUTOMATION_JITTER: f32 = 0.3;
+/// Places along the paddle the targeting AI tries sending the ball from.
+const AIM_CANDIDATES: usize = 17;
+/// Furthest out from the middle of the paddle the aiming AIs catch the ball,
+/// the very edge is too easy to miss.
+const MAX_AIM_OFFSET: f32 = 0.9;
+
+/// What a controller can see of the game when deciding where the paddle goes.
+#[derive(Debug, Clone, Copy)]
+pub struct FieldView<'a> {
+    pub balls: &'a [Entity],
+    pub paddle: &'a Entity,
+    pub bricks: &'a [Entity],
+    pub size: Vector,
+    pub step_seconds: f32,
+}
+
+/// Where the paddle goes next.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Steer {
+    /// Applied the same way as input from the player.
+    pub input: Input,
+    /// Column the middle of the paddle heads for at the automation speed.
+    pub follow: Option<f32>,
+}
+
+/// Decides how the paddle moves every step, from what the player did, what
+/// is happening on the field, or both.
+pub trait PaddleController: std::fmt::Debug {
+    /// Short name shown in the header.
+    fn name(&self) -> &str;
+
+    /// Whether the controller plays without the player, rounds launch and
+    /// restart on their own while it is in charge.
+    fn is_automatic(&self) -> bool;
+
+    /// Most the game randomly moves where the ball touches the paddle while
+    /// this controller plays, as a fraction of half the paddle.
+    fn deflection_jitter(&self) -> f32 {
+        if self.is_automatic() {
+            AUTOMATION_JITTER
+        } else {
+            0.0
+        }
+    }
+
+    fn steer(&mut self, player: Input, view: &FieldView<'_>, rng: &mut ChaCha8Rng) -> Steer;
+}
+
+/// The controllers built into the game, in the order the header cycles
+/// through them.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ControllerKind {
+    #[default]
+    Keyboard,
+    Mouse,
+    /// Follows the ball to wherever it is going to land.
+    Predictive,
+    /// Catches the ball on a random part of the paddle.
+    Random,
+    /// Sends the ball towards the densest group of bricks.
+    Cluster,
+    /// Sends the ball along whichever path breaks the most bricks.
+    Targeting,
+}
+
+impl ControllerKind {
+    const ALL: [ControllerKind; 6] = [
+        ControllerKind::Keyboard,
+        ControllerKind::Mouse,
+        ControllerKind::Predictive,
+        ControllerKind::Random,
+        ControllerKind::Cluster,
+        ControllerKind::Targeting,
+    ];
+
+    pub fn build(&self) -> Box<dyn PaddleController> {
+        match self {
+            ControllerKind::Keyboard => Box::new(KeyboardController),
+            ControllerKind::Mouse => Box::new(MouseController),
+            ControllerKind::Predictive => Box::new(PredictiveController),
+            ControllerKind::Random => Box::new(RandomController::default()),
+            ControllerKind::Cluster => Box::new(ClusterController),
+            ControllerKind::Targeting => Box::new(TargetingController::default()),
+        }
+    }
+
+    /// Place in the cycle, which is how templates pass the controller around.
+    pub fn index(&self) -> usize {
+        Self::ALL.iter().position(|kind| kind == self).unwrap_or(0)
+    }
+
+    /// The controller at `index` in the cycle, the default when there is none.
+    pub fn from_index(index: usize) -> Self {
+        Self::ALL.get(index).copied().unwrap_or_default()
+    }
+
+    /// The controller after this one, wrapping around to the first.
+    pub fn next(&self) -> Self {
+        Self::ALL[(self.index() + 1) % Self::ALL.len()]
+    }
+
+    pub fn is_automatic(&self) -> bool {
+        !matches!(self, ControllerKind::Keyboard | ControllerKind::Mouse)
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ControllerKind::Keyboard => "keyboard",
+            ControllerKind::Mouse => "mouse",
+            ControllerKind::Predictive => "predictive",
+            ControllerKind::Random => "random",
+            ControllerKind::Cluster => "cluster",
+            ControllerKind::Targeting => "targeting",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.as_str() == name)
+    }
+}
+
+/// The arrow keys push the paddle.
+#[derive(Debug)]
+pub struct KeyboardController;
+
+impl PaddleController for KeyboardController {
+    fn name(&self) -> &str {
+        ControllerKind::Keyboard.as_str()
+    }
+
+    fn is_automatic(&self) -> bool {
+        false
+    }
+
+    fn steer(&mut self, player: Input, _view: &FieldView<'_>, _rng: &mut ChaCha8Rng) -> Steer {
+        Steer {
+            input: Input {
+                paddle_target: None,
+                ..player
+            },
+            follow: None,
+        }
+    }
+}
+
+/// The paddle is centered under the mouse.
+#[derive(Debug)]
+pub struct MouseController;
+
+impl PaddleController for MouseController {
+    fn name(&self) -> &str {
+        ControllerKind::Mouse.as_str()
+    }
+
+    fn is_automatic(&self) -> bool {
+        false
+    }
+
+    fn steer(&mut self, player: Input, _view: &FieldView<'_>, _rng: &mut ChaCha8Rng) -> Steer {
+        Steer {
+            input: Input {
+                paddle_force: 0,
+                ..player
+            },
+            follow: None,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct PredictiveController;
+
+impl PaddleController for PredictiveController {
+    fn name(&self) -> &str {
+        ControllerKind::Predictive.as_str()
+    }
+
+    fn is_automatic(&self) -> bool {
+        true
+    }
+
+    fn steer(&mut self, _player: Input, view: &FieldView<'_>, _rng: &mut ChaCha8Rng) -> Steer {
+        Steer {
+            follow: predict_landing(view).map(|landing| landing.x),
+            ..Steer::default()
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct RandomController {
+    /// Where on the paddle the next catch happens, from -1 on the left edge
+    /// to 1 on the right edge.
+    offset: f32,
+    /// Whether the ball was already on its way down last step.
+    falling: bool,
+}
+
+impl PaddleController for RandomController {
+    fn name(&self) -> &str {
+        ControllerKind::Random.as_str()
+    }
+
+    fn is_automatic(&self) -> bool {
+        true
+    }
+
+    fn steer(&mut self, _player: Input, view: &FieldView<'_>, rng: &mut ChaCha8Rng) -> Steer {
+        let falling = first_to_land(view).is_some_and(|ball| ball.velocity.y > 0.0);
+
+        // a new spot on the paddle every time the ball starts coming down
+        if falling && !self.falling {
+            self.offset = rng.random_range(-0.9..0.9);
+        }
+
+        self.falling = falling;
+
+        let half_width = view.paddle.size.x / 2.0;
+
+        Steer {
+            follow: predict_landing(view).map(|landing| landing.x - self.offset * half_width),
+            ..Steer::default()
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ClusterController;
+
+impl PaddleController for ClusterController {
+    fn name(&self) -> &str {
+        ControllerKind::Cluster.as_str()
+    }
+
+    fn is_automatic(&self) -> bool {
+        true
+    }
+
+    fn steer(&mut self, _player: Input, view: &FieldView<'_>, _rng: &mut ChaCha8Rng) -> Steer {
+        let Some(landing) = predict_landing(view) else {
+            return Steer::default();
+        };
+        let ball_x = landing.x;
+
+        let Some(target) = densest_cluster(view.bricks) else {
+            return Steer {
+                follow: Some(ball_x),
+                ..Steer::default()
+            };
+        };
+
+        // catch the ball on the part of the paddle that sends it at the target
+        let angle = (target.x - ball_x).atan2(view.paddle.position.y - target.y);
+        let offset = (angle / MAX_DEFLECTION).clamp(-MAX_AIM_OFFSET, MAX_AIM_OFFSET);
+        let half_width = view.paddle.size.x / 2.0;
+
+        Steer {
+            follow: Some(ball_x - offset * half_width),
+            ..Steer::default()
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct TargetingController {
+    /// The ball's velocity and the bricks left when the plan was made, the
+    /// plan holds until either changes.
+    planned_for: Option<(Vector, usize)>,
+    follow: Option<f32>,
+}
+
+impl PaddleController for TargetingController {
+    fn name(&self) -> &str {
+        ControllerKind::Targeting.as_str()
+    }
+
+    fn is_automatic(&self) -> bool {
+        true
+    }
+
+    /// Aiming only works when the ball goes where it is sent.
+    fn deflection_jitter(&self) -> f32 {
+        0.0
+    }
+
+    fn steer(&mut self, _player: Input, view: &FieldView<'_>, rng: &mut ChaCha8Rng) -> Steer {
+        let Some(ball) = first_to_land(view) else {
+            return Steer::default();
+        };
+        let planned_for = Some((ball.velocity, view.bricks.len()));
+
+        if self.planned_for != planned_for {
+            self.planned_for = planned_for;
+            self.follow = plan_shot(view, rng);
+        }
+
+        Steer {
+            follow: self.follow,
+            ..Steer::default()
+        }
+    }
+}
+
+/// Where the middle of the paddle should be to send the first ball to land
+/// along the path that breaks the most bricks.
+fn plan_shot(view: &FieldView<'_>, rng: &mut ChaCha8Rng) -> Option<f32> {
+    let landing = predict_landing(view)?;
+    let ball_x = landing.x;
+    let origin = Vector::new(ball_x, landing.y - 0.5);
+    let half_width = view.paddle.size.x / 2.0;
+
+    // near a wall some offsets need the paddle to be partly outside the field
+    let lowest = ((ball_x - (view.size.x - half_width)) / half_width).max(-MAX_AIM_OFFSET);
+    let highest = ((ball_x - half_width) / half_width).min(MAX_AIM_OFFSET);
+
+    if lowest >= highest {
+        return Some(ball_x);
+    }
+
+    let (hits, offset) = (0..AIM_CANDIDATES)
+        .map(|index| {
+            let offset = lowest + (highest - lowest) * index as f32 / (AIM_CANDIDATES - 1) as f32;
+
+            let hits = Trajectory::trace(origin, deflection(offset), view).bricks_broken;
+
+            (hits, offset)
+        })
+        // the most bricks, then the path closest to straight up
+        .max_by(|(a_hits, a), (b_hits, b)| a_hits.cmp(b_hits).then(b.abs().total_cmp(&a.abs())))?;
+
+    // with nothing in reach any angle will do, a random one keeps the ball
+    // from going back and forth along the same empty path
+    let offset = if hits == 0 {
+        rng.random_range(lowest..highest)
+    } else {
+        offset
+    };
+
+    Some(ball_x - offset * half_width)
+}
+
+/// The ball that will reach the paddle first, falling balls before rising
+/// ones and lower balls before higher ones.
+fn first_to_land<'a>(view: &FieldView<'a>) -> Option<&'a Entity> {
+    view.balls.iter().max_by(|a, b| {
+        (a.velocity.y > 0.0, a.position.y)
+            .partial_cmp(&(b.velocity.y > 0.0, b.position.y))
+            .unwrap_or(std::cmp::Ordering::Equal)
+    })
+}
+
+/// The path of the first ball to land, bouncing off the walls and the
+/// bricks in its way.
+pub fn predict_trajectory(view: &FieldView<'_>) -> Option<Trajectory> {
+    let ball = first_to_land(view)?;
+    let center = ball.position + Vector::new(0.5, 0.5);
+
+    Some(Trajectory::trace(center, ball.velocity, view))
+}
+
+/// Where the middle of the first ball to land will touch the top of the
+/// paddle.
+fn predict_landing(view: &FieldView<'_>) -> Option<Vector> {
+    predict_trajectory(view)?.landing
+}
+
+/// The middle of the brick with the most bricks around it.
+fn densest_cluster(bricks: &[Entity]) -> Option<Vector> {
+    let centers = bricks
+        .iter()
+        .filter(|brick| brick.is_required())
+        .map(|brick| brick.position + brick.size * 0.5)
+        .collect::<Vec<_>>();
+
+    centers.iter().copied().max_by_key(|center| {
+        centers
+            .iter()
+            .filter(|other| {
+                (other.x - center.x).abs() <= CLUSTER_REACH.x
+                    && (other.y - center.y).abs() <= CLUSTER_REACH.y
+            })
+            .count()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+
+    use super::*;
+    use crate::game::{
+        entity::EntityColor,
+        simulation::{GameEvent, Simulation, Timing},
+    };
+
+    fn entity(x: f32, y: f32, width: f32, height: f32, health: usize) -> Entity {
+        Entity::new(
+            Vector::new(x, y),
+            Vector::new(width, height),
+            ' ',
+            EntityColor::Red,
+            health,
+        )
+    }
+
+    /// Where the targeting AI puts the middle of the paddle for a ball
+    /// falling straight down onto the paddle at x = 20.5, among `bricks`.
+    fn planned_shot(bricks: &[Entity]) -> Option<f32> {
+        let mut ball = entity(20.0, 10.0, 1.0, 1.0, 1);
+        ball.velocity = Vector::new(0.0, 30.0);
+        let paddle = entity(15.0, 20.0, 10.0, 2.0, 1);
+        let view = FieldView {
+            balls: &[ball],
+            paddle: &paddle,
+            bricks,
+            size: Vector::new(40.0, 22.0),
+            step_seconds: 1.0 / 60.0,
+        };
+
+        plan_shot(&view, &mut ChaCha8Rng::seed_from_u64(0))
+    }
+
+    /// Let `kind` play the classic bricks for `seconds`, returning how many
+    /// balls it missed.
+    fn misses(kind: ControllerKind, seconds: u32) -> usize {
+        let timing = Timing::default();
+        let mut simulation = Simulation::new(Vector::new(80.0, 22.0), vec![], 1, timing);
+
+        simulation.set_controller(kind.build());
+        simulation.reset(1);
+
+        (0..seconds * timing.tick_rate)
+            .flat_map(|_| simulation.step(Input::default()))
+            .filter(|event| *event == GameEvent::BallLost)
+            .count()
+    }
+
+    #[test]
+    fn the_cluster_controller_keeps_the_ball_in_play() {
+        assert_eq!(misses(ControllerKind::Cluster, 120), 0);
+    }
+
+    #[test]
+    fn the_targeting_controller_sends_the_ball_where_it_breaks_the_most() {
+        // straight up from the landing spot
+        let above = entity(20.0, 4.0, 1.0, 1.0, 1);
+        // only reached by catching the ball 0.45 of the way out to the
+        // paddle's right edge, with its middle 2.25 cells left of the ball
+        let aside = entity(27.5, 4.0, 1.0, 1.0, 1);
+
+        // both paths break a brick, so the one closest to straight up wins
+        let follow = planned_shot(&[above, aside]).unwrap();
+        assert!((follow - 20.5).abs() < 0.001);
+
+        // a brick that takes two hits isn't broken by the path straight up
+        let tough = entity(20.0, 4.0, 1.0, 1.0, 2);
+        let follow = planned_shot(&[tough, aside]).unwrap();
+        assert!((follow - (20.5 - 2.25)).abs() < 0.001);
+    }
+}
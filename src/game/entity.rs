@@ -1,18 +1,52 @@
-use anathema::{default_widgets::Canvas, state::Color, widgets::Style};
-
 use crate::game::vector::Vector;
 
+/// Colors the game can paint entities with, kept separate from the
+/// renderer's colors so the simulation doesn't depend on it.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum EntityColor {
+    #[default]
+    Reset,
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    Grey,
+    White,
+}
+
+/// What happens when a brick is destroyed, beyond scoring its points.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum BrickKind {
+    #[default]
+    Normal,
+    /// Splits every ball in play into three.
+    MultiBall,
+    /// Bounces the ball but never breaks, and isn't needed to clear a level.
+    Indestructible,
+    /// Destroys the bricks around it when it breaks, setting off any other
+    /// explosive bricks caught in the blast.
+    Explosive,
+    /// Heals back to full health when left alone for a while.
+    Regenerating,
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct Entity {
     pub position: Vector,
     pub size: Vector,
-    character: char,
+    pub character: char,
     pub velocity: Vector,
     pub is_alive: bool,
-    pub bg_color: Color,
+    pub bg_color: EntityColor,
     pub health: usize,
     pub original_health: usize,
     pub value: usize,
+    pub kind: BrickKind,
+    /// Steps since the entity last lost health.
+    pub ticks_since_hit: u32,
 }
 
 impl Entity {
@@ -20,7 +54,7 @@ impl Entity {
         position: Vector,
         size: Vector,
         character: char,
-        bg_color: Color,
+        bg_color: EntityColor,
         health: usize,
     ) -> Self {
         Self {
@@ -33,29 +67,8 @@ impl Entity {
             health,
             original_health: health,
             value: health,
-        }
-    }
-
-    pub fn draw(&self, canvas: &mut Canvas) {
-        let mut style = Style::new();
-        let lost_health_character = [self.character, '/', '\\', '#'];
-        let damage = self.original_health - self.health;
-        let character = lost_health_character[damage];
-
-        style.set_bg(self.bg_color);
-
-        if damage > 0 {
-            style.set_fg(Color::Black);
-        }
-
-        for row in 0..self.size.x {
-            for col in 0..self.size.y {
-                canvas.put(
-                    character,
-                    style,
-                    (row + self.position.x, col + self.position.y),
-                );
-            }
+            kind: BrickKind::Normal,
+            ticks_since_hit: 0,
         }
     }
 
@@ -63,30 +76,44 @@ impl Entity {
         self.velocity += force;
     }
 
-    pub fn apply_velocity(&mut self) {
-        self.position += self.velocity;
+    /// Move for `seconds`, velocity is in cells per second.
+    pub fn apply_velocity(&mut self, seconds: f32) {
+        self.position += self.velocity * seconds;
     }
 
-    pub fn update(&mut self, game_size: Vector) {
-        self.apply_velocity();
+    pub fn update(&mut self, game_size: Vector, seconds: f32) {
+        self.apply_velocity(seconds);
         self.bounce_off_walls(&game_size);
     }
 
     pub fn bounce_off_walls(&mut self, game_size: &Vector) {
-        if self.position.x <= 0 {
-            self.position.x = 0;
-            self.velocity.x *= -1;
-        } else if self.position.x >= game_size.x - 1 {
-            self.position.x = game_size.x - 1;
-            self.velocity.x *= -1;
+        if self.position.x <= 0.0 {
+            self.position.x = 0.0;
+            self.velocity.x *= -1.0;
+        } else if self.position.x >= game_size.x - 1.0 {
+            self.position.x = game_size.x - 1.0;
+            self.velocity.x *= -1.0;
         }
 
-        if self.position.y <= 0 {
-            self.position.y = 0;
-            self.velocity.y *= -1;
+        if self.position.y <= 0.0 {
+            self.position.y = 0.0;
+            self.velocity.y *= -1.0;
         }
     }
 
+    /// Move back inside a field of `game_size`, keeping the whole entity in
+    /// view.
+    pub fn keep_inside(&mut self, game_size: Vector) {
+        self.position.x = self
+            .position
+            .x
+            .clamp(0.0, (game_size.x - self.size.x).max(0.0));
+        self.position.y = self
+            .position
+            .y
+            .clamp(0.0, (game_size.y - self.size.y).max(0.0));
+    }
+
     pub fn is_point_inside(&self, point: &Vector) -> bool {
         point.x >= self.position.x
             && point.x < self.position.x + self.size.x
@@ -94,11 +121,17 @@ impl Entity {
             && point.y < self.position.y + self.size.y
     }
 
-    pub fn previous_location(&self) -> Vector {
-        self.position - self.velocity
+    pub fn lose_health(&mut self) {
+        if self.kind == BrickKind::Indestructible {
+            return;
+        }
+
+        self.health = self.health.saturating_sub(1);
+        self.ticks_since_hit = 0;
     }
 
-    pub fn lose_health(&mut self) {
-        self.health -= 1;
+    /// Whether the brick has to be destroyed to clear the level.
+    pub fn is_required(&self) -> bool {
+        self.kind != BrickKind::Indestructible
     }
 }
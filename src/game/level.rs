@@ -0,0 +1,524 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use eyre::{Result, WrapErr, bail, eyre};
+
+use crate::game::{
+    entity::{BrickKind, Entity, EntityColor},
+    vector::Vector,
+};
+
+const LEVEL_EXTENSION: &str = "level";
+const CLASSIC_LEVEL: &str = include_str!("../../levels/01_classic.level");
+/// Cells per second the bricks of oscillating rows slide sideways.
+const OSCILLATE_SPEED: f32 = 4.0;
+/// Widest a brick is stretched before each column of the layout is repeated
+/// to fill the field instead.
+const MAX_BRICK_WIDTH: usize = 12;
+
+/// How hard a level is to play. Level 1 matches the original game and every
+/// level after that speeds the ball up, toughens the bricks and shrinks the
+/// paddle until the caps are reached.
+#[derive(Debug, Clone, Copy)]
+pub struct Difficulty {
+    /// How much faster than the configured speed the ball starts a round.
+    pub ball_speed_scale: f32,
+    pub extra_brick_health: usize,
+    pub paddle_width: i32,
+}
+
+impl Difficulty {
+    pub fn for_level(level: u8) -> Self {
+        let stage = level.saturating_sub(1);
+
+        Self {
+            ball_speed_scale: (1.0 + stage as f32 * 0.15).min(2.0),
+            extra_brick_health: (stage / 2) as usize,
+            paddle_width: (10 - stage as i32).max(4),
+        }
+    }
+}
+
+impl Default for Difficulty {
+    fn default() -> Self {
+        Self::for_level(1)
+    }
+}
+
+/// What a single glyph in a level layout turns into.
+#[derive(Debug, Clone, Copy)]
+pub struct BrickSpec {
+    pub color: EntityColor,
+    pub health: usize,
+    pub points: usize,
+    pub kind: BrickKind,
+}
+
+/// A brick layout read from a level file.
+///
+/// Level files have a `[bricks]` section mapping glyphs to bricks and a
+/// `[layout]` section with the grid itself:
+///
+/// ```text
+/// # comments start with a hash, anywhere in the file
+/// name = Classic
+/// # rows that slide from side to side, counted from the top
+/// oscillate = 2
+///
+/// [bricks]
+/// # glyph = color health points [kind]
+/// R = red 1 1
+/// B = white 1 5 multiball
+///
+/// [layout]
+/// RRRR.RRRR
+/// ```
+///
+/// A `.` or a space in the layout leaves the cell empty. Bricks without a
+/// kind are normal bricks, the other kinds are `multiball`, `indestructible`,
+/// `explosive` and `regenerating`.
+#[derive(Debug, Clone)]
+pub struct LevelLayout {
+    pub name: String,
+    rows: Vec<Vec<Option<BrickSpec>>>,
+    /// Rows, counted from 0, whose bricks slide from side to side.
+    oscillating_rows: Vec<usize>,
+}
+
+impl LevelLayout {
+    pub fn parse(source: &str, path: &Path) -> Result<Self> {
+        let mut name = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let mut section = Section::Header;
+        let mut glyphs: Vec<(char, BrickSpec)> = vec![];
+        let mut rows = vec![];
+        let mut oscillating_rows = vec![];
+
+        for (index, line) in source.lines().enumerate() {
+            let line_number = index + 1;
+            let location = |column: usize| format!("{}:{line_number}:{column}", path.display());
+            let trimmed = line.trim();
+
+            // blank lines in the layout are empty rows, but comments are
+            // skipped everywhere, which is why `#` can't be a brick glyph
+            if trimmed.starts_with('#') || (section != Section::Layout && trimmed.is_empty()) {
+                continue;
+            }
+
+            if trimmed.starts_with('[') {
+                section = match trimmed {
+                    "[bricks]" => Section::Bricks,
+                    "[layout]" => Section::Layout,
+                    _ => bail!(
+                        "{}: unknown section {trimmed}",
+                        location(column_of(line, trimmed))
+                    ),
+                };
+                continue;
+            }
+
+            match section {
+                Section::Header => {
+                    let Some((key, value)) = line.split_once('=') else {
+                        bail!(
+                            "{}: expected `key = value`",
+                            location(column_of(line, trimmed))
+                        );
+                    };
+
+                    let key = key.trim();
+
+                    match key {
+                        "name" => name = value.trim().to_owned(),
+                        "oscillate" => {
+                            for row in value.split_whitespace() {
+                                let number = row
+                                    .parse::<usize>()
+                                    .ok()
+                                    .filter(|number| *number > 0)
+                                    .ok_or_else(|| {
+                                        eyre!(
+                                            "{}: rows are counted from 1, found `{row}`",
+                                            location(column_of(line, row))
+                                        )
+                                    })?;
+
+                                oscillating_rows.push(number - 1);
+                            }
+                        }
+                        _ => bail!(
+                            "{}: unknown setting `{key}`",
+                            location(column_of(line, key))
+                        ),
+                    }
+                }
+                Section::Bricks => {
+                    let (glyph, spec) = parse_brick(line)
+                        .map_err(|(column, message)| eyre!("{}: {message}", location(column)))?;
+
+                    if glyphs.iter().any(|(existing, _)| *existing == glyph) {
+                        bail!(
+                            "{}: glyph '{glyph}' is defined more than once",
+                            location(column_of(line, trimmed))
+                        );
+                    }
+
+                    glyphs.push((glyph, spec));
+                }
+                Section::Layout => {
+                    if trimmed.is_empty() && rows.is_empty() {
+                        continue;
+                    }
+
+                    let mut row = vec![];
+
+                    for (column, glyph) in line.trim_end().chars().enumerate() {
+                        if glyph == '.' || glyph == ' ' {
+                            row.push(None);
+                            continue;
+                        }
+
+                        let Some((_, spec)) = glyphs.iter().find(|(known, _)| *known == glyph)
+                        else {
+                            bail!("{}: unknown brick glyph '{glyph}'", location(column + 1));
+                        };
+
+                        row.push(Some(*spec));
+                    }
+
+                    rows.push(row);
+                }
+            }
+        }
+
+        while rows.last().is_some_and(|row| row.is_empty()) {
+            rows.pop();
+        }
+
+        if rows.iter().flatten().all(Option::is_none) {
+            bail!(
+                "{}: level has no bricks in its [layout] section",
+                path.display()
+            );
+        }
+
+        Ok(Self {
+            name,
+            rows,
+            oscillating_rows,
+        })
+    }
+
+    pub fn classic() -> Self {
+        Self::parse(CLASSIC_LEVEL, Path::new("levels/01_classic.level"))
+            .expect("the built in level is valid")
+    }
+
+    /// Build the bricks for this layout, stretching the grid across the
+    /// field so every row fills the full width. Every column of the layout
+    /// is kept, repeated side by side when the field is too wide for a
+    /// single brick to cover it.
+    pub fn build_bricks(&self, game_width: i32, difficulty: &Difficulty) -> Vec<Entity> {
+        let game_width = game_width.max(1) as usize;
+        let columns = self
+            .rows
+            .iter()
+            .map(Vec::len)
+            .max()
+            .unwrap_or_default()
+            .max(1);
+        let repeats = game_width.div_ceil(columns * MAX_BRICK_WIDTH);
+        let bricks_per_row = columns * repeats;
+        let brick_character = ' ';
+        let mut bricks = vec![];
+
+        for (row_index, row) in self.rows.iter().enumerate() {
+            // neighbouring rows set off in opposite directions
+            let velocity = if !self.oscillating_rows.contains(&row_index) {
+                Vector::zero()
+            } else if row_index % 2 == 0 {
+                Vector::new(OSCILLATE_SPEED, 0.0)
+            } else {
+                Vector::new(-OSCILLATE_SPEED, 0.0)
+            };
+
+            for count in 0..bricks_per_row {
+                let Some(Some(spec)) = row.get(count / repeats) else {
+                    continue;
+                };
+                // the width left over is spread along the row, so bricks
+                // differ by a cell at most and the row ends on the far wall
+                let left = count * game_width / bricks_per_row;
+                let right = (count + 1) * game_width / bricks_per_row;
+                let position = Vector::new(left as f32, row_index as f32);
+                let size = Vector::new((right - left).max(1) as f32, 1.0);
+                let health = spec.health + difficulty.extra_brick_health;
+                let mut brick = Entity::new(position, size, brick_character, spec.color, health);
+
+                brick.value = spec.points;
+                brick.kind = spec.kind;
+                brick.velocity = velocity;
+                bricks.push(brick);
+            }
+        }
+
+        bricks
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Section {
+    Header,
+    Bricks,
+    Layout,
+}
+
+/// Load every `.level` file in `directory`, sorted by file name. When the
+/// directory doesn't exist the built in classic layout is used instead.
+pub fn load_levels(directory: &Path) -> Result<Vec<LevelLayout>> {
+    if !directory.exists() {
+        return Ok(vec![LevelLayout::classic()]);
+    }
+
+    let mut paths = fs::read_dir(directory)
+        .wrap_err_with(|| format!("could not read level directory {}", directory.display()))?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<Vec<PathBuf>, _>>()
+        .wrap_err_with(|| format!("could not read level directory {}", directory.display()))?;
+
+    paths.retain(|path| path.extension().is_some_and(|ext| ext == LEVEL_EXTENSION));
+    paths.sort();
+
+    if paths.is_empty() {
+        return Ok(vec![LevelLayout::classic()]);
+    }
+
+    paths
+        .iter()
+        .map(|path| {
+            let source = fs::read_to_string(path)
+                .wrap_err_with(|| format!("could not read level file {}", path.display()))?;
+
+            LevelLayout::parse(&source, path)
+        })
+        .collect()
+}
+
+/// Parse a `glyph = color health points [kind]` line, returning the column and
+/// message of the first problem found.
+fn parse_brick(line: &str) -> Result<(char, BrickSpec), (usize, String)> {
+    let Some((glyph, definition)) = line.split_once('=') else {
+        return Err((
+            column_of(line, line.trim()),
+            "expected `glyph = color health points [kind]`".to_owned(),
+        ));
+    };
+
+    let mut glyph_chars = glyph.trim().chars();
+    let (Some(glyph_char), None) = (glyph_chars.next(), glyph_chars.next()) else {
+        return Err((
+            column_of(line, glyph.trim()),
+            "a brick glyph must be a single character".to_owned(),
+        ));
+    };
+
+    if glyph_char == '.' {
+        return Err((
+            column_of(line, glyph.trim()),
+            "'.' is reserved for empty cells".to_owned(),
+        ));
+    }
+
+    let fields = definition.split_whitespace().collect::<Vec<_>>();
+    let field_column = |field: &str| column_of(line, field);
+
+    let (color, health, points, kind) = match fields[..] {
+        [color, health, points] => (color, health, points, None),
+        [color, health, points, kind] => (color, health, points, Some(kind)),
+        _ => {
+            return Err((
+                column_of(line, definition),
+                format!(
+                    "expected `color health points [kind]`, found {} fields",
+                    fields.len()
+                ),
+            ));
+        }
+    };
+
+    let color = parse_color(color)
+        .ok_or_else(|| (field_column(color), format!("unknown color `{color}`")))?;
+    let health = health
+        .parse::<usize>()
+        .ok()
+        .filter(|health| *health > 0)
+        .ok_or_else(|| {
+            (
+                field_column(health),
+                format!("health must be a positive number, found `{health}`"),
+            )
+        })?;
+    let points = points.parse::<usize>().map_err(|_| {
+        (
+            field_column(points),
+            format!("points must be a number, found `{points}`"),
+        )
+    })?;
+
+    let kind = match kind {
+        None => BrickKind::Normal,
+        Some(kind) => parse_kind(kind)
+            .ok_or_else(|| (field_column(kind), format!("unknown brick kind `{kind}`")))?,
+    };
+
+    Ok((
+        glyph_char,
+        BrickSpec {
+            color,
+            health,
+            points,
+            kind,
+        },
+    ))
+}
+
+fn parse_color(name: &str) -> Option<EntityColor> {
+    let color = match name.to_lowercase().as_str() {
+        "black" => EntityColor::Black,
+        "red" => EntityColor::Red,
+        "green" => EntityColor::Green,
+        "yellow" => EntityColor::Yellow,
+        "blue" => EntityColor::Blue,
+        "magenta" => EntityColor::Magenta,
+        "cyan" => EntityColor::Cyan,
+        "grey" | "gray" => EntityColor::Grey,
+        "white" => EntityColor::White,
+        _ => return None,
+    };
+
+    Some(color)
+}
+
+fn parse_kind(name: &str) -> Option<BrickKind> {
+    let kind = match name.to_lowercase().as_str() {
+        "normal" => BrickKind::Normal,
+        "multiball" => BrickKind::MultiBall,
+        "indestructible" => BrickKind::Indestructible,
+        "explosive" => BrickKind::Explosive,
+        "regenerating" => BrickKind::Regenerating,
+        _ => return None,
+    };
+
+    Some(kind)
+}
+
+/// 1-based column of `needle` within `line`, counted in characters like the
+/// columns of the layout, `needle` must be a slice of `line`.
+fn column_of(line: &str, needle: &str) -> usize {
+    let offset = needle.as_ptr() as usize - line.as_ptr() as usize;
+
+    line[..offset].chars().count() + 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(source: &str) -> Result<LevelLayout> {
+        LevelLayout::parse(source, Path::new("test.level"))
+    }
+
+    fn error(source: &str) -> String {
+        parse(source).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn errors_point_at_the_line_and_column() {
+        assert_eq!(
+            error("name = Test\nspeed = 3\n"),
+            "test.level:2:1: unknown setting `speed`"
+        );
+        assert_eq!(
+            error("[bricks]\nR = red one 1\n"),
+            "test.level:2:9: health must be a positive number, found `one`"
+        );
+        assert_eq!(
+            error("[bricks]\nR = red 1 1\n[layout]\nRR.Rx\n"),
+            "test.level:4:5: unknown brick glyph 'x'"
+        );
+        assert_eq!(
+            error("oscillate = 2 0\n"),
+            "test.level:1:15: rows are counted from 1, found `0`"
+        );
+    }
+
+    #[test]
+    fn error_columns_count_characters() {
+        // the first glyph is three bytes long but a single column
+        assert_eq!(
+            error("[bricks]\n▓ = grey 1 0 indestructible\n[layout]\n▓▓x\n"),
+            "test.level:4:3: unknown brick glyph 'x'"
+        );
+        assert_eq!(
+            error("[bricks]\n▓ = pink 1 0\n"),
+            "test.level:2:5: unknown color `pink`"
+        );
+    }
+
+    #[test]
+    fn comments_are_skipped_in_every_section() {
+        let layout = parse(
+            "# header\nname = Test\n[bricks]\n# glyph = color health points\nR = red 1 1\n\
+             [layout]\n# top row\nRR\n\n# after a gap\nRR\n",
+        )
+        .unwrap();
+
+        assert_eq!(layout.name, "Test");
+        // the blank line is an empty row between the two
+        assert_eq!(layout.rows.len(), 3);
+    }
+
+    #[test]
+    fn a_level_needs_bricks() {
+        assert_eq!(
+            error("[bricks]\nR = red 1 1\n[layout]\n....\n"),
+            "test.level: level has no bricks in its [layout] section"
+        );
+    }
+
+    #[test]
+    fn every_authored_column_becomes_a_brick() {
+        let layout = parse("[bricks]\nR = red 1 1\n[layout]\nR.RR.RR.RR.R\n").unwrap();
+        let bricks = layout.build_bricks(80, &Difficulty::default());
+        let spans = bricks
+            .iter()
+            .map(|brick| (brick.position.x, brick.position.x + brick.size.x))
+            .collect::<Vec<_>>();
+
+        assert_eq!(bricks.len(), 8);
+        // the twelfth column is kept and reaches the far wall
+        assert_eq!(spans.last(), Some(&(73.0, 80.0)));
+        // the gap in the second column is kept too
+        assert_eq!(spans[0], (0.0, 6.0));
+        assert_eq!(spans[1].0, 13.0);
+    }
+
+    #[test]
+    fn wide_fields_repeat_columns_instead_of_dropping_them() {
+        let layout = parse("[bricks]\nR = red 1 1\nB = blue 1 1\n[layout]\nRB\n").unwrap();
+        let bricks = layout.build_bricks(60, &Difficulty::default());
+
+        assert_eq!(bricks.len(), 6);
+        assert!(
+            bricks
+                .iter()
+                .all(|brick| brick.size.x <= MAX_BRICK_WIDTH as f32)
+        );
+        assert_eq!(bricks[2].bg_color, EntityColor::Red);
+        assert_eq!(bricks[3].bg_color, EntityColor::Blue);
+    }
+}
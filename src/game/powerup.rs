@@ -0,0 +1,216 @@
+use rand::Rng;
+use rand_chacha::ChaCha8Rng;
+
+use crate::game::{
+    entity::{Entity, EntityColor},
+    vector::Vector,
+};
+
+/// Chance a destroyed brick drops a capsule.
+pub const DROP_CHANCE: f64 = 0.15;
+/// Cells per second a capsule falls.
+const CAPSULE_FALL_SPEED: f32 = 8.0;
+const CAPSULE_SIZE: Vector = Vector { x: 3.0, y: 1.0 };
+
+/// What catching a capsule does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PowerUpKind {
+    WidePaddle,
+    NarrowPaddle,
+    SlowBall,
+    /// The ball sticks to the paddle until it is launched.
+    StickyPaddle,
+    ExtraLife,
+    ScoreMultiplier,
+    /// Every ball in play splits into three.
+    MultiBall,
+    /// The action button fires shots from both ends of the paddle.
+    Laser,
+}
+
+impl PowerUpKind {
+    const ALL: [PowerUpKind; 8] = [
+        PowerUpKind::WidePaddle,
+        PowerUpKind::NarrowPaddle,
+        PowerUpKind::SlowBall,
+        PowerUpKind::StickyPaddle,
+        PowerUpKind::ExtraLife,
+        PowerUpKind::ScoreMultiplier,
+        PowerUpKind::MultiBall,
+        PowerUpKind::Laser,
+    ];
+
+    pub fn random(rng: &mut ChaCha8Rng) -> Self {
+        Self::ALL[rng.random_range(0..Self::ALL.len())]
+    }
+
+    /// How long the effect lasts, effects without a duration happen once.
+    pub fn duration_seconds(&self) -> Option<f32> {
+        match self {
+            PowerUpKind::WidePaddle | PowerUpKind::NarrowPaddle => Some(15.0),
+            PowerUpKind::SlowBall => Some(10.0),
+            PowerUpKind::StickyPaddle => Some(12.0),
+            PowerUpKind::ScoreMultiplier => Some(20.0),
+            PowerUpKind::Laser => Some(10.0),
+            PowerUpKind::ExtraLife | PowerUpKind::MultiBall => None,
+        }
+    }
+
+    pub fn glyph(&self) -> char {
+        match self {
+            PowerUpKind::WidePaddle => 'W',
+            PowerUpKind::NarrowPaddle => 'N',
+            PowerUpKind::SlowBall => 'S',
+            PowerUpKind::StickyPaddle => 'G',
+            PowerUpKind::ExtraLife => 'L',
+            PowerUpKind::ScoreMultiplier => 'x',
+            PowerUpKind::MultiBall => 'B',
+            PowerUpKind::Laser => 'Z',
+        }
+    }
+
+    pub fn color(&self) -> EntityColor {
+        match self {
+            PowerUpKind::WidePaddle => EntityColor::Green,
+            PowerUpKind::NarrowPaddle => EntityColor::Red,
+            PowerUpKind::SlowBall => EntityColor::Blue,
+            PowerUpKind::StickyPaddle => EntityColor::Yellow,
+            PowerUpKind::ExtraLife => EntityColor::Magenta,
+            PowerUpKind::ScoreMultiplier => EntityColor::Cyan,
+            PowerUpKind::MultiBall => EntityColor::White,
+            PowerUpKind::Laser => EntityColor::Grey,
+        }
+    }
+
+    /// Short name shown next to the timer in the header.
+    pub fn label(&self) -> &'static str {
+        match self {
+            PowerUpKind::WidePaddle => "wide",
+            PowerUpKind::NarrowPaddle => "narrow",
+            PowerUpKind::SlowBall => "slow",
+            PowerUpKind::StickyPaddle => "sticky",
+            PowerUpKind::ExtraLife => "life",
+            PowerUpKind::ScoreMultiplier => "x2",
+            PowerUpKind::MultiBall => "multi",
+            PowerUpKind::Laser => "laser",
+        }
+    }
+
+    /// Effects that can't be active at the same time as this one.
+    fn cancels(&self, other: PowerUpKind) -> bool {
+        matches!(
+            (self, other),
+            (PowerUpKind::WidePaddle, PowerUpKind::NarrowPaddle)
+                | (PowerUpKind::NarrowPaddle, PowerUpKind::WidePaddle)
+        )
+    }
+}
+
+/// A power-up falling from a destroyed brick towards the paddle.
+#[derive(Debug, Clone, Copy)]
+pub struct Capsule {
+    pub entity: Entity,
+    pub kind: PowerUpKind,
+}
+
+impl Capsule {
+    /// Drop a capsule from the middle of `brick`.
+    pub fn dropped_from(brick: &Entity, kind: PowerUpKind) -> Self {
+        let position = Vector::new(
+            (brick.position.x + (brick.size.x - CAPSULE_SIZE.x) / 2.0).floor(),
+            brick.position.y,
+        );
+        let mut entity = Entity::new(position, CAPSULE_SIZE, kind.glyph(), kind.color(), 1);
+        entity.velocity = Vector::new(0.0, CAPSULE_FALL_SPEED);
+
+        Self { entity, kind }
+    }
+
+    pub fn overlaps(&self, other: &Entity) -> bool {
+        let capsule = &self.entity;
+
+        capsule.position.x < other.position.x + other.size.x
+            && other.position.x < capsule.position.x + capsule.size.x
+            && capsule.position.y < other.position.y + other.size.y
+            && other.position.y < capsule.position.y + capsule.size.y
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct ActiveEffect {
+    kind: PowerUpKind,
+    remaining_ticks: u32,
+}
+
+/// The timed effects currently in play.
+#[derive(Debug, Default, Clone)]
+pub struct Effects {
+    active: Vec<ActiveEffect>,
+}
+
+impl Effects {
+    /// Start `kind` for `ticks`, restarting its timer if it is already running.
+    pub fn activate(&mut self, kind: PowerUpKind, ticks: u32) {
+        self.active
+            .retain(|effect| effect.kind != kind && !kind.cancels(effect.kind));
+        self.active.push(ActiveEffect {
+            kind,
+            remaining_ticks: ticks,
+        });
+    }
+
+    /// Count down every timer, dropping the effects that ran out.
+    pub fn tick(&mut self) {
+        for effect in self.active.iter_mut() {
+            effect.remaining_ticks = effect.remaining_ticks.saturating_sub(1);
+        }
+
+        self.active.retain(|effect| effect.remaining_ticks > 0);
+    }
+
+    pub fn clear(&mut self) {
+        self.active.clear();
+    }
+
+    pub fn is_active(&self, kind: PowerUpKind) -> bool {
+        self.active.iter().any(|effect| effect.kind == kind)
+    }
+
+    pub fn paddle_scale(&self) -> f32 {
+        if self.is_active(PowerUpKind::WidePaddle) {
+            1.5
+        } else if self.is_active(PowerUpKind::NarrowPaddle) {
+            0.6
+        } else {
+            1.0
+        }
+    }
+
+    pub fn ball_speed_scale(&self) -> f32 {
+        if self.is_active(PowerUpKind::SlowBall) {
+            0.6
+        } else {
+            1.0
+        }
+    }
+
+    pub fn score_multiplier(&self) -> usize {
+        if self.is_active(PowerUpKind::ScoreMultiplier) {
+            2
+        } else {
+            1
+        }
+    }
+
+    /// Every active effect with the whole seconds it has left, e.g. `wide 12s`.
+    pub fn summary(&self, tick_rate: u32) -> String {
+        self.active
+            .iter()
+            .map(|effect| {
+                let seconds = effect.remaining_ticks.div_ceil(tick_rate.max(1));
+                format!("{} {seconds}s", effect.kind.label())
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
@@ -0,0 +1,125 @@
+use anathema::{default_widgets::Canvas, state::Color, widgets::Style};
+
+use crate::game::{
+    entity::{BrickKind, Entity, EntityColor},
+    powerup::Capsule,
+    scoring::FloatingText,
+    trajectory::Trajectory,
+    vector::Vector,
+};
+
+impl Entity {
+    pub fn draw(&self, canvas: &mut Canvas) {
+        let mut style = Style::new();
+        let lost_health_character = [self.kind_character(), '/', '\\', '#'];
+        let damage = self.original_health.saturating_sub(self.health);
+        let character = lost_health_character[damage.min(lost_health_character.len() - 1)];
+
+        style.set_bg(self.bg_color.into());
+
+        if damage > 0 || self.kind != BrickKind::Normal {
+            style.set_fg(Color::Black);
+        }
+
+        // entities can sit between cells, they are drawn in the closest one
+        let x = self.position.x.round() as i32;
+        let y = self.position.y.round() as i32;
+
+        for row in 0..self.size.x as i32 {
+            for col in 0..self.size.y as i32 {
+                canvas.put(character, style, (row + x, col + y));
+            }
+        }
+    }
+
+    /// Special bricks are filled with a pattern so they can be told apart
+    /// from normal bricks of the same color.
+    fn kind_character(&self) -> char {
+        match self.kind {
+            BrickKind::Normal => self.character,
+            BrickKind::MultiBall => 'o',
+            BrickKind::Indestructible => '▓',
+            BrickKind::Explosive => '*',
+            BrickKind::Regenerating => '+',
+        }
+    }
+}
+
+impl Capsule {
+    /// Capsules are drawn as their glyph in brackets, e.g. `[W]`.
+    pub fn draw(&self, canvas: &mut Canvas) {
+        let mut style = Style::new();
+        style.set_bg(self.entity.bg_color.into());
+        style.set_fg(Color::Black);
+
+        let x = self.entity.position.x.round() as i32;
+        let y = self.entity.position.y.round() as i32;
+
+        for (offset, character) in ['[', self.kind.glyph(), ']'].into_iter().enumerate() {
+            canvas.put(character, style, (x + offset as i32, y));
+        }
+    }
+}
+
+impl From<EntityColor> for Color {
+    fn from(color: EntityColor) -> Self {
+        match color {
+            EntityColor::Reset => Color::Reset,
+            EntityColor::Black => Color::Black,
+            EntityColor::Red => Color::Red,
+            EntityColor::Green => Color::Green,
+            EntityColor::Yellow => Color::Yellow,
+            EntityColor::Blue => Color::Blue,
+            EntityColor::Magenta => Color::Magenta,
+            EntityColor::Cyan => Color::Cyan,
+            EntityColor::Grey => Color::Grey,
+            EntityColor::White => Color::White,
+        }
+    }
+}
+
+impl FloatingText {
+    /// Floating text is drawn over whatever is beneath it.
+    pub fn draw(&self, canvas: &mut Canvas) {
+        let mut style = Style::new();
+        style.set_fg(Color::Yellow);
+
+        let x = self.position.x.round() as i32;
+        let y = self.position.y.round() as i32;
+
+        for (offset, character) in self.text.chars().enumerate() {
+            canvas.put(character, style, (x + offset as i32, y));
+        }
+    }
+}
+
+impl Trajectory {
+    /// The path is dotted in cell by cell, with a cross where the ball will
+    /// land, and is meant to be drawn before anything it could cover.
+    pub fn draw(&self, canvas: &mut Canvas) {
+        let mut style = Style::new();
+        style.set_fg(Color::Grey);
+
+        for segment in self.points.windows(2) {
+            let travel = segment[1] - segment[0];
+            let steps = travel.x.abs().max(travel.y.abs()).ceil().max(1.0) as usize;
+
+            for step in 0..steps {
+                let point = segment[0] + travel * (step as f32 / steps as f32);
+                canvas.put('·', style, ball_cell(point));
+            }
+        }
+
+        if let Some(landing) = self.landing {
+            canvas.put('x', style, ball_cell(landing));
+        }
+    }
+}
+
+/// The cell a ball whose middle is at `center` is drawn in.
+fn ball_cell(center: Vector) -> (i32, i32) {
+    (
+        (center.x - 0.5).round() as i32,
+        (center.y - 0.5).round() as i32,
+    )
+}
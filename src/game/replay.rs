@@ -0,0 +1,483 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use eyre::{Result, WrapErr, bail, eyre};
+
+use crate::game::{
+    controller::ControllerKind,
+    simulation::{Advance, Input, Timing},
+    vector::Vector,
+};
+
+const HEADER: &str = "# anathema breakout replay";
+const REPLAY_DIRECTORY: &str = "replays";
+const REPLAY_EXTENSION: &str = "replay";
+
+/// Something that changed the course of a game at a given tick.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReplayAction {
+    /// A round was started on this level.
+    Begin(u8),
+    /// The level was started over from its full set of bricks.
+    Restart(u8),
+    /// The terminal was resized and the field with it.
+    Resize(Vector),
+    Input(Input),
+    /// The paddle was handed over to another controller.
+    Controller(ControllerKind),
+}
+
+/// Everything needed to play a game again exactly as it happened: the seed,
+/// the size of the field, the timing, and every action along with the tick it
+/// happened on.
+///
+/// Replays are saved as text, one action per line:
+///
+/// ```text
+/// # anathema breakout replay
+/// seed 1234
+/// size 80 28
+/// rate 60
+/// ball_speed 30
+/// advance 10s
+/// 0 begin 1
+/// 12 force -1
+/// 30 target 40
+/// 42 action
+/// 55 controller predictive
+/// 80 resize 100 30
+/// ```
+#[derive(Debug, Clone)]
+pub struct Replay {
+    pub seed: u64,
+    pub size: Vector,
+    pub timing: Timing,
+    pub advance: Option<Advance>,
+    actions: Vec<(u64, ReplayAction)>,
+}
+
+impl Replay {
+    pub fn new(seed: u64, size: Vector, timing: Timing, advance: Option<Advance>) -> Self {
+        Self {
+            seed,
+            size,
+            timing,
+            advance,
+            actions: vec![],
+        }
+    }
+
+    pub fn record(&mut self, tick: u64, action: ReplayAction) {
+        if let ReplayAction::Input(input) = action
+            && input == Input::default()
+        {
+            return;
+        }
+
+        self.actions.push((tick, action));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.actions.is_empty()
+    }
+
+    /// Write the replay into the replays directory, returning where it went.
+    pub fn save(&self) -> Result<PathBuf> {
+        let directory = Path::new(REPLAY_DIRECTORY);
+        let seconds = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or_default();
+        let path = directory.join(format!("{seconds}-seed-{}.{REPLAY_EXTENSION}", self.seed));
+
+        fs::create_dir_all(directory)
+            .wrap_err_with(|| format!("could not create {}", directory.display()))?;
+        fs::write(&path, self.to_text())
+            .wrap_err_with(|| format!("could not write replay {}", path.display()))?;
+
+        Ok(path)
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let source = fs::read_to_string(path)
+            .wrap_err_with(|| format!("could not read replay {}", path.display()))?;
+
+        Self::parse(&source, path)
+    }
+
+    fn to_text(&self) -> String {
+        let mut lines = vec![
+            HEADER.to_owned(),
+            format!("seed {}", self.seed),
+            format!("size {} {}", self.size.x, self.size.y),
+            format!("rate {}", self.timing.tick_rate),
+            format!("ball_speed {}", self.timing.ball_speed),
+        ];
+
+        if let Some(advance) = self.advance {
+            lines.push(format!("advance {advance}"));
+        }
+
+        for (tick, action) in &self.actions {
+            match action {
+                ReplayAction::Begin(level) => lines.push(format!("{tick} begin {level}")),
+                ReplayAction::Restart(level) => lines.push(format!("{tick} restart {level}")),
+                ReplayAction::Resize(size) => {
+                    lines.push(format!("{tick} resize {} {}", size.x, size.y))
+                }
+                ReplayAction::Controller(kind) => {
+                    lines.push(format!("{tick} controller {}", kind.as_str()))
+                }
+                ReplayAction::Input(input) => {
+                    if input.paddle_force != 0 {
+                        lines.push(format!("{tick} force {}", input.paddle_force));
+                    }
+
+                    if let Some(target) = input.paddle_target {
+                        lines.push(format!("{tick} target {target}"));
+                    }
+
+                    if input.action {
+                        lines.push(format!("{tick} action"));
+                    }
+                }
+            }
+        }
+
+        lines.join("\n") + "\n"
+    }
+
+    fn parse(source: &str, path: &Path) -> Result<Self> {
+        let mut seed = None;
+        let mut size = None;
+        // replays from before the timing was recorded used the defaults
+        let mut timing = Timing::default();
+        let mut advance = None;
+        let mut actions: Vec<(u64, ReplayAction)> = vec![];
+
+        for (index, line) in source.lines().enumerate() {
+            let location = format!("{}:{}", path.display(), index + 1);
+            let fields = line.split_whitespace().collect::<Vec<_>>();
+
+            match fields[..] {
+                [] => continue,
+                [first, ..] if first.starts_with('#') => continue,
+                ["seed", value] => seed = Some(parse_number(value, &location)?),
+                ["size", width, height] => {
+                    size = Some(Vector::new(
+                        parse_number(width, &location)?,
+                        parse_number(height, &location)?,
+                    ))
+                }
+                ["rate", value] => {
+                    timing.tick_rate = parse_number(value, &location)?;
+
+                    if timing.tick_rate == 0 {
+                        bail!("{location}: rate must be above zero");
+                    }
+                }
+                ["ball_speed", value] => timing.ball_speed = parse_number(value, &location)?,
+                ["advance", value] => {
+                    advance = Some(Advance::parse(value).ok_or_else(|| {
+                        eyre!("{location}: expected an advance like `10s` or `6hits`")
+                    })?)
+                }
+                [tick, kind] | [tick, kind, _] | [tick, kind, _, _] => {
+                    let tick = parse_number(tick, &location)?;
+                    let value = fields.get(2).copied().unwrap_or_default();
+                    let second_value = fields.get(3).copied().unwrap_or_default();
+
+                    if actions.last().is_some_and(|(last, _)| *last > tick) {
+                        bail!("{location}: tick {tick} is earlier than the line before it");
+                    }
+
+                    let action = match kind {
+                        "begin" => ReplayAction::Begin(parse_number(value, &location)?),
+                        "restart" => ReplayAction::Restart(parse_number(value, &location)?),
+                        "resize" => ReplayAction::Resize(Vector::new(
+                            parse_number(value, &location)?,
+                            parse_number(second_value, &location)?,
+                        )),
+                        "controller" => ReplayAction::Controller(
+                            ControllerKind::parse(value)
+                                .ok_or_else(|| eyre!("{location}: unknown controller `{value}`"))?,
+                        ),
+                        // replays from before there were controllers to choose from
+                        "automation" => ReplayAction::Controller(match value {
+                            "on" => ControllerKind::Predictive,
+                            "off" => ControllerKind::Keyboard,
+                            _ => bail!("{location}: automation must be `on` or `off`"),
+                        }),
+                        "force" => ReplayAction::Input(Input {
+                            paddle_force: parse_number(value, &location)?,
+                            ..Input::default()
+                        }),
+                        "target" => ReplayAction::Input(Input {
+                            paddle_target: Some(parse_number(value, &location)?),
+                            ..Input::default()
+                        }),
+                        "action" => ReplayAction::Input(Input {
+                            action: true,
+                            ..Input::default()
+                        }),
+                        _ => bail!("{location}: unknown action `{kind}`"),
+                    };
+
+                    // inputs from the same tick were written as separate lines
+                    match (actions.last_mut(), action) {
+                        (
+                            Some((last_tick, ReplayAction::Input(last))),
+                            ReplayAction::Input(input),
+                        ) if *last_tick == tick => {
+                            last.paddle_force += input.paddle_force;
+                            last.paddle_target = input.paddle_target.or(last.paddle_target);
+                            last.action |= input.action;
+                        }
+                        _ => actions.push((tick, action)),
+                    }
+                }
+                _ => bail!("{location}: could not understand `{}`", line.trim()),
+            }
+        }
+
+        let seed = seed.ok_or_else(|| eyre!("{}: replay has no seed", path.display()))?;
+        let size = size.ok_or_else(|| eyre!("{}: replay has no size", path.display()))?;
+
+        Ok(Self {
+            seed,
+            size,
+            timing,
+            advance,
+            actions,
+        })
+    }
+}
+
+fn parse_number<T: std::str::FromStr>(value: &str, location: &str) -> Result<T> {
+    value
+        .parse()
+        .map_err(|_| eyre!("{location}: expected a number, found `{value}`"))
+}
+
+/// Feeds a replay back one tick at a time, with the controls for watching it.
+#[derive(Debug)]
+pub struct Playback {
+    replay: Replay,
+    cursor: usize,
+    pub paused: bool,
+    /// How many times faster than real time the replay plays.
+    pub speed: u8,
+    step_requested: bool,
+}
+
+impl Playback {
+    pub fn new(replay: Replay) -> Self {
+        Self {
+            replay,
+            cursor: 0,
+            paused: false,
+            speed: 1,
+            step_requested: false,
+        }
+    }
+
+    pub fn replay(&self) -> &Replay {
+        &self.replay
+    }
+
+    /// Advance a single tick while paused.
+    pub fn request_step(&mut self) {
+        self.step_requested = true;
+    }
+
+    /// How much replay time passes while `elapsed` passes for the viewer.
+    pub fn scale_elapsed(&self, elapsed: Duration) -> Duration {
+        if self.paused {
+            Duration::ZERO
+        } else {
+            elapsed * self.speed as u32
+        }
+    }
+
+    /// Whether a single step was asked for since the last call.
+    pub fn take_step_request(&mut self) -> bool {
+        std::mem::take(&mut self.step_requested)
+    }
+
+    /// The actions recorded for `tick`, in the order they happened.
+    pub fn actions_at(&mut self, tick: u64) -> Vec<ReplayAction> {
+        let mut actions = vec![];
+
+        while let Some((action_tick, action)) = self.replay.actions.get(self.cursor) {
+            if *action_tick > tick {
+                break;
+            }
+
+            if *action_tick == tick {
+                actions.push(*action);
+            }
+
+            self.cursor += 1;
+        }
+
+        actions
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.cursor >= self.replay.actions.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::simulation::{GameEvent, Simulation};
+
+    fn path() -> &'static Path {
+        Path::new("test.replay")
+    }
+
+    #[test]
+    fn a_replay_reads_back_the_way_it_was_written() {
+        let timing = Timing {
+            tick_rate: 120,
+            ball_speed: 25.5,
+        };
+        let mut replay = Replay::new(
+            1234,
+            Vector::new(80.0, 28.0),
+            timing,
+            Some(Advance::PaddleHits(6)),
+        );
+
+        replay.record(0, ReplayAction::Begin(1));
+        replay.record(
+            12,
+            ReplayAction::Input(Input {
+                paddle_force: -1,
+                paddle_target: Some(40),
+                action: true,
+            }),
+        );
+        replay.record(55, ReplayAction::Controller(ControllerKind::Targeting));
+        replay.record(80, ReplayAction::Resize(Vector::new(100.0, 30.0)));
+        replay.record(90, ReplayAction::Restart(2));
+
+        let parsed = Replay::parse(&replay.to_text(), path()).unwrap();
+
+        assert_eq!(parsed.seed, 1234);
+        assert_eq!(parsed.size, Vector::new(80.0, 28.0));
+        assert_eq!(parsed.timing, timing);
+        assert_eq!(parsed.advance, Some(Advance::PaddleHits(6)));
+        assert_eq!(parsed.actions, replay.actions);
+    }
+
+    /// Carry out `action` on `simulation` the way the game does while a
+    /// replay is watched, keeping any input it brings in `input`.
+    fn apply(simulation: &mut Simulation, action: ReplayAction, input: &mut Input) {
+        match action {
+            ReplayAction::Begin(level) => simulation.reset(level),
+            ReplayAction::Restart(level) => simulation.restart(level),
+            ReplayAction::Resize(size) => simulation.resize(size),
+            ReplayAction::Input(recorded) => *input = recorded,
+            ReplayAction::Controller(kind) => simulation.set_controller(kind.build()),
+        }
+    }
+
+    #[test]
+    fn a_restarted_level_plays_back_the_way_it_was_played() {
+        let size = Vector::new(40.0, 20.0);
+        let restart_tick = 300;
+        let mut replay = Replay::new(5, size, Timing::default(), None);
+        let mut live = Simulation::new(size, vec![], 5, Timing::default());
+        let mut live_events: Vec<GameEvent> = vec![];
+
+        for tick in 0..600 {
+            let actions = match tick {
+                0 => vec![
+                    ReplayAction::Begin(1),
+                    ReplayAction::Controller(ControllerKind::Predictive),
+                ],
+                _ if tick == restart_tick => vec![ReplayAction::Restart(1)],
+                _ => vec![],
+            };
+            let mut input = Input::default();
+
+            for action in actions {
+                replay.record(tick, action);
+                apply(&mut live, action, &mut input);
+            }
+
+            live_events.extend(live.step(input));
+        }
+
+        let mut playback = Playback::new(Replay::parse(&replay.to_text(), path()).unwrap());
+        let mut played = Simulation::new(size, vec![], playback.replay().seed, Timing::default());
+        let mut played_events: Vec<GameEvent> = vec![];
+
+        for tick in 0..600 {
+            let actions = playback.actions_at(tick);
+            let mut input = Input::default();
+
+            if tick == restart_tick {
+                assert_eq!(actions, [ReplayAction::Restart(1)]);
+            }
+
+            for action in actions {
+                apply(&mut played, action, &mut input);
+            }
+
+            played_events.extend(played.step(input));
+        }
+
+        assert!(playback.is_finished());
+        assert_eq!(played_events, live_events);
+        assert_eq!(played.entities().bricks.len(), live.entities().bricks.len());
+        assert_eq!(
+            played.entities().balls[0].position,
+            live.entities().balls[0].position
+        );
+    }
+
+    #[test]
+    fn idle_ticks_are_not_recorded() {
+        let mut replay = Replay::new(1, Vector::new(80.0, 28.0), Timing::default(), None);
+
+        replay.record(3, ReplayAction::Input(Input::default()));
+
+        assert!(replay.is_empty());
+    }
+
+    #[test]
+    fn automation_from_old_replays_becomes_a_controller() {
+        let source = "seed 1\nsize 80 28\n5 automation on\n9 automation off\n";
+        let parsed = Replay::parse(source, path()).unwrap();
+
+        assert_eq!(
+            parsed.actions,
+            [
+                (5, ReplayAction::Controller(ControllerKind::Predictive)),
+                (9, ReplayAction::Controller(ControllerKind::Keyboard)),
+            ]
+        );
+    }
+
+    #[test]
+    fn problems_are_reported_with_their_line() {
+        let error = Replay::parse("seed 1\nsize 80 28\n9 begin 1\n4 action\n", path())
+            .unwrap_err()
+            .to_string();
+        assert_eq!(
+            error,
+            "test.replay:4: tick 4 is earlier than the line before it"
+        );
+
+        let error = Replay::parse("size 80 28\n", path())
+            .unwrap_err()
+            .to_string();
+        assert_eq!(error, "test.replay: replay has no seed");
+    }
+}
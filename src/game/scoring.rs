@@ -0,0 +1,120 @@
+use crate::game::{entity::Entity, vector::Vector};
+
+/// The most a combo can multiply a brick's points by.
+pub const MAX_COMBO: usize = 8;
+/// Points for destroying the last brick in a row.
+pub const ROW_BONUS: usize = 25;
+/// Points for every life left when a level is cleared.
+const LIFE_BONUS: usize = 100;
+/// Clearing a level faster than this earns points for every second to spare.
+const PAR_SECONDS: f32 = 120.0;
+const POINTS_PER_SPARE_SECOND: usize = 5;
+/// Seconds a score stays floating over the field.
+const FLOAT_SECONDS: f32 = 1.0;
+/// Cells per second a score floats up the field.
+const FLOAT_SPEED: f32 = 3.0;
+
+/// Points earned for something, and where on the field they were earned.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Score {
+    /// Points with every multiplier already applied.
+    pub points: usize,
+    pub multiplier: usize,
+    pub position: Vector,
+}
+
+impl Score {
+    /// The points for destroying `brick`, before any multiplier.
+    pub fn brick(brick: &Entity) -> Self {
+        Self {
+            points: brick.value,
+            multiplier: 1,
+            position: brick.position + brick.size * 0.5,
+        }
+    }
+
+    /// Apply `multiplier` on top of any applied before.
+    pub fn multiply(&mut self, multiplier: usize) {
+        self.points *= multiplier;
+        self.multiplier *= multiplier;
+    }
+
+    /// How the score is shown over the field, e.g. `+6 x3`.
+    pub fn label(&self) -> String {
+        if self.multiplier > 1 {
+            format!("+{} x{}", self.points, self.multiplier)
+        } else {
+            format!("+{}", self.points)
+        }
+    }
+}
+
+/// Points for clearing a level with `lives` to spare after `seconds`.
+pub fn level_clear_bonus(lives: usize, seconds: f32) -> usize {
+    let spare_seconds = (PAR_SECONDS - seconds).max(0.0) as usize;
+
+    lives * LIFE_BONUS + spare_seconds * POINTS_PER_SPARE_SECOND
+}
+
+/// A score drifting up from where it was earned before fading away.
+#[derive(Debug, Clone)]
+pub struct FloatingText {
+    pub text: String,
+    pub position: Vector,
+    seconds_left: f32,
+}
+
+impl FloatingText {
+    /// Float `text` centered over `position`.
+    pub fn new(text: String, position: Vector) -> Self {
+        let width = text.chars().count() as f32;
+
+        Self {
+            position: Vector::new((position.x - width / 2.0).max(0.0), position.y),
+            text,
+            seconds_left: FLOAT_SECONDS,
+        }
+    }
+
+    pub fn update(&mut self, seconds: f32) {
+        self.position.y -= FLOAT_SPEED * seconds;
+        self.seconds_left -= seconds;
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.seconds_left <= 0.0 || self.position.y < 0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lives_and_spare_seconds_earn_the_level_clear_bonus() {
+        assert_eq!(
+            level_clear_bonus(2, 100.0),
+            2 * LIFE_BONUS + 20 * POINTS_PER_SPARE_SECOND
+        );
+        // no time to spare
+        assert_eq!(level_clear_bonus(1, 300.0), LIFE_BONUS);
+        assert_eq!(level_clear_bonus(0, PAR_SECONDS), 0);
+    }
+
+    #[test]
+    fn multipliers_stack_and_show_in_the_label() {
+        let mut score = Score {
+            points: 3,
+            multiplier: 1,
+            position: Vector::zero(),
+        };
+
+        assert_eq!(score.label(), "+3");
+
+        score.multiply(3);
+        score.multiply(2);
+
+        assert_eq!(score.points, 18);
+        assert_eq!(score.label(), "+18 x6");
+    }
+}
@@ -0,0 +1,1383 @@
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::game::{
+    collision::{Face, back_off, sweep, sweep_walls},
+    controller::{ControllerKind, FieldView, PaddleController, predict_trajectory},
+    entity::{BrickKind, Entity, EntityColor},
+    level::{Difficulty, LevelLayout},
+    powerup::{Capsule, DROP_CHANCE, Effects, PowerUpKind},
+    scoring::{MAX_COMBO, ROW_BONUS, Score},
+    trajectory::Trajectory,
+    vector::Vector,
+};
+
+/// The smallest field the game is played on, room for a few rows of bricks,
+/// the paddle, and space for the ball to travel between them. Smaller fields
+/// are played at this size and cut off when drawn.
+pub const MIN_FIELD_SIZE: Vector = Vector { x: 40.0, y: 14.0 };
+/// Lives a game starts with, not counting the ball in play.
+pub const STARTING_LIVES: u8 = 3;
+/// Extra lives from power-ups stop adding up past this.
+pub const MAX_LIVES: u8 = 9;
+/// Enough to bounce around a corner of bricks without looping forever.
+const MAX_CONTACTS_PER_MOVE: usize = 8;
+/// The steepest angle away from straight up the paddle can send the ball.
+pub const MAX_DEFLECTION: f32 = std::f32::consts::FRAC_PI_3;
+/// Every paddle hit speeds the ball up by this much.
+const BALL_SPEED_UP: f32 = 1.02;
+/// How much faster than its starting speed the ball can get in a round.
+const MAX_BALL_SPEED_UP: f32 = 1.5;
+/// Cells per second every key press adds to the paddle's speed.
+const PADDLE_PUSH: f32 = 20.0;
+/// Cells per second the paddle moves when it plays itself.
+const AUTOMATION_PADDLE_SPEED: f32 = 60.0;
+/// No more balls are split off once this many are in play.
+const MAX_BALLS: usize = 12;
+/// How far from the original direction split off balls head, in radians.
+const SPLIT_ANGLE: f32 = 0.35;
+/// Cells per second laser shots travel up the field.
+const LASER_SPEED: f32 = 40.0;
+/// Seconds between laser shots.
+const LASER_COOLDOWN_SECONDS: f32 = 0.4;
+/// Cells past its edges an explosive brick destroys its neighbours in.
+const EXPLOSION_RADIUS: f32 = 1.0;
+/// Seconds a damaged regenerating brick has to be left alone to heal.
+const REGENERATE_SECONDS: f32 = 5.0;
+
+/// Everything the player did since the last step.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Input {
+    /// Added to the paddle's velocity, the way the arrow keys push it.
+    pub paddle_force: i32,
+    /// Column to center the paddle on, the way the mouse places it.
+    pub paddle_target: Option<i32>,
+    /// The action button was pressed, letting go of a held ball or firing
+    /// the laser.
+    pub action: bool,
+}
+
+/// Things that happened during a step that the outside world may care about.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GameEvent {
+    Scored(Score),
+    LostLife,
+    LevelCleared(u8),
+    /// A capsule was caught and its effect started.
+    PowerUp(PowerUpKind),
+    /// The bricks came down as far as the paddle, ending the game.
+    Overrun,
+    /// A ball bounced off the paddle.
+    PaddleHit,
+    /// A ball fell past the paddle, whether or not others are still in play.
+    BallLost,
+}
+
+/// How often the simulation steps and how fast the ball moves, independent
+/// of how often the game is drawn.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Timing {
+    /// Steps per second.
+    pub tick_rate: u32,
+    /// Cells per second the ball starts a round at on the first level.
+    pub ball_speed: f32,
+}
+
+impl Timing {
+    /// Seconds covered by a single step.
+    pub fn step_seconds(&self) -> f32 {
+        1.0 / self.tick_rate.max(1) as f32
+    }
+}
+
+impl Default for Timing {
+    fn default() -> Self {
+        Self {
+            tick_rate: 60,
+            ball_speed: 30.0,
+        }
+    }
+}
+
+/// When the brick field moves down a row, the rule behind survival mode.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Advance {
+    Seconds(f32),
+    PaddleHits(u32),
+}
+
+impl Advance {
+    /// Parse `10s` for every ten seconds or `6hits` for every six paddle hits.
+    pub fn parse(value: &str) -> Option<Self> {
+        if let Some(hits) = value.strip_suffix("hits") {
+            return hits
+                .parse()
+                .ok()
+                .filter(|hits| *hits > 0)
+                .map(Advance::PaddleHits);
+        }
+
+        value
+            .strip_suffix('s')?
+            .parse()
+            .ok()
+            .filter(|seconds: &f32| *seconds > 0.0 && seconds.is_finite())
+            .map(Advance::Seconds)
+    }
+}
+
+impl std::fmt::Display for Advance {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Advance::Seconds(seconds) => write!(f, "{seconds}s"),
+            Advance::PaddleHits(hits) => write!(f, "{hits}hits"),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Rules {
+    pub difficulty: Difficulty,
+    pub timing: Timing,
+    /// Bring the bricks down a row at a time, ending the game when they
+    /// reach the paddle.
+    pub advance: Option<Advance>,
+    /// The paddle plays itself and launches the ball on its own, set by the
+    /// controller in charge.
+    pub automation_mode: bool,
+    /// Rounds restart on their own instead of ending with an event, set by
+    /// the controller in charge.
+    pub endless: bool,
+    /// Most the game randomly moves where the ball touches the paddle, as a
+    /// fraction of half the paddle, set by the controller in charge.
+    pub deflection_jitter: f32,
+}
+
+#[derive(Debug)]
+pub struct GameEntities {
+    /// Every ball in play, the round goes on while there is at least one.
+    pub balls: Vec<Entity>,
+    pub paddle: Option<Entity>,
+    pub bricks: Vec<Entity>,
+    pub capsules: Vec<Capsule>,
+    /// Laser shots on their way up the field.
+    pub projectiles: Vec<Entity>,
+    /// Every random decision goes through here so a seed replays a game exactly.
+    rng: ChaCha8Rng,
+}
+
+impl GameEntities {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            balls: vec![],
+            paddle: None,
+            bricks: vec![],
+            capsules: vec![],
+            projectiles: vec![],
+            rng: ChaCha8Rng::seed_from_u64(seed),
+        }
+    }
+}
+
+/// The rules of breakout without any knowledge of how the game is shown or
+/// where its input comes from.
+#[derive(Debug)]
+pub struct Simulation {
+    entities: GameEntities,
+    size: Vector,
+    rules: Rules,
+    levels: Vec<LevelLayout>,
+    level: u8,
+    /// Cells per second the ball moves, creeping up as the round goes on.
+    ball_speed: f32,
+    effects: Effects,
+    /// Moves the paddle, for the player or by itself.
+    controller: Box<dyn PaddleController>,
+    /// Balls resting on the paddle, waiting to be launched.
+    held_balls: Vec<HeldBall>,
+    /// Ticks until the laser can fire again.
+    laser_cooldown: u32,
+    /// Ticks and paddle hits since the bricks last moved down.
+    ticks_since_advance: u32,
+    hits_since_advance: u32,
+    /// Bricks broken since a ball last touched the paddle.
+    combo: usize,
+    /// Ticks spent on the current level's bricks.
+    level_ticks: u32,
+}
+
+/// A ball resting on the paddle, riding along with it until launched.
+#[derive(Debug, Clone, Copy)]
+struct HeldBall {
+    /// Which of the balls is held.
+    index: usize,
+    /// Where on the paddle the ball sits, from its left edge.
+    offset: f32,
+}
+
+impl Simulation {
+    pub fn new(size: Vector, levels: Vec<LevelLayout>, seed: u64, timing: Timing) -> Self {
+        Self {
+            entities: GameEntities::new(seed),
+            size,
+            rules: Rules {
+                timing,
+                ..Rules::default()
+            },
+            levels,
+            level: 0,
+            ball_speed: 0.0,
+            effects: Effects::default(),
+            controller: ControllerKind::default().build(),
+            held_balls: vec![],
+            laser_cooldown: 0,
+            ticks_since_advance: 0,
+            hits_since_advance: 0,
+            combo: 0,
+            level_ticks: 0,
+        }
+    }
+
+    /// Throw away the current game and start over from nothing with `seed`,
+    /// handing the paddle back to the default controller.
+    pub fn new_game(&mut self, seed: u64) {
+        self.entities = GameEntities::new(seed);
+        self.level = 0;
+        self.ball_speed = 0.0;
+        self.effects.clear();
+        self.held_balls.clear();
+        self.laser_cooldown = 0;
+        self.combo = 0;
+        self.level_ticks = 0;
+        // replays only record switching controllers, so every game has to
+        // start out with the same one and none of the last game's plans
+        self.set_controller(ControllerKind::default().build());
+    }
+
+    pub fn entities(&self) -> &GameEntities {
+        &self.entities
+    }
+
+    pub fn effects(&self) -> &Effects {
+        &self.effects
+    }
+
+    pub fn size(&self) -> Vector {
+        self.size
+    }
+
+    pub fn set_size(&mut self, size: Vector) {
+        self.size = at_least_min_size(size);
+    }
+
+    /// Fit the game into a field of `size`, stretching the bricks across the
+    /// new width and moving everything else proportionally while keeping it
+    /// inside the field.
+    pub fn resize(&mut self, size: Vector) {
+        let size = at_least_min_size(size);
+        let old_size = self.size;
+        self.size = size;
+
+        if old_size == size || old_size.x <= 0.0 || old_size.y <= 0.0 {
+            return;
+        }
+
+        let scale = Vector::new(size.x / old_size.x, size.y / old_size.y);
+        let GameEntities {
+            balls,
+            paddle,
+            bricks,
+            capsules,
+            projectiles,
+            ..
+        } = &mut self.entities;
+
+        // rows stay where they are so they don't run into each other, scaling
+        // both edges keeps neighbouring bricks touching
+        for brick in bricks.iter_mut() {
+            let left = (brick.position.x * scale.x).round();
+            let right = ((brick.position.x + brick.size.x) * scale.x).round();
+
+            brick.position.x = left;
+            brick.size.x = (right - left).max(1.0);
+        }
+
+        if let Some(paddle) = paddle {
+            paddle.position.x = (paddle.position.x * scale.x).round();
+            paddle.position.y = size.y - paddle.size.y;
+            paddle.keep_inside(size);
+        }
+
+        let moving = balls
+            .iter_mut()
+            .chain(projectiles.iter_mut())
+            .chain(capsules.iter_mut().map(|capsule| &mut capsule.entity));
+
+        for entity in moving {
+            entity.position = Vector::new(entity.position.x * scale.x, entity.position.y * scale.y);
+            entity.keep_inside(size);
+        }
+
+        // a ball that ended up inside a brick is dropped out underneath it
+        for ball in balls.iter_mut() {
+            while let Some(brick) = bricks
+                .iter()
+                .find(|brick| brick.is_point_inside(&(ball.position + ball.size * 0.5)))
+            {
+                ball.position.y = brick.position.y + brick.size.y;
+            }
+        }
+    }
+
+    pub fn level(&self) -> u8 {
+        self.level
+    }
+
+    /// The name the current level's file gives it, levels wrap around once
+    /// every file has been played.
+    pub fn level_name(&self) -> &str {
+        match self.levels.len() {
+            0 => "",
+            count => &self.levels[(self.level.max(1) as usize - 1) % count].name,
+        }
+    }
+
+    /// Seconds spent on the current level's bricks, across lost lives.
+    pub fn level_seconds(&self) -> f32 {
+        self.level_ticks as f32 * self.rules.timing.step_seconds()
+    }
+
+    pub fn timing(&self) -> Timing {
+        self.rules.timing
+    }
+
+    pub fn set_timing(&mut self, timing: Timing) {
+        self.rules.timing = timing;
+    }
+
+    pub fn set_advance(&mut self, advance: Option<Advance>) {
+        self.rules.advance = advance;
+    }
+
+    pub fn automation_mode(&self) -> bool {
+        self.rules.automation_mode
+    }
+
+    pub fn controller_name(&self) -> &str {
+        self.controller.name()
+    }
+
+    /// Hand the paddle over to `controller` from the next step on.
+    pub fn set_controller(&mut self, controller: Box<dyn PaddleController>) {
+        self.rules.automation_mode = controller.is_automatic();
+        self.rules.endless = controller.is_automatic();
+        self.rules.deflection_jitter = controller.deflection_jitter();
+        self.controller = controller;
+    }
+
+    /// Report the end of every round even when the paddle plays itself, so
+    /// whoever drives the simulation decides what happens next.
+    pub fn set_endless(&mut self, endless: bool) {
+        self.rules.endless = endless;
+    }
+
+    /// Where the first ball to land is headed, for drawing an aim guide. A
+    /// ball held on the paddle shows the path it would take if launched now.
+    pub fn trajectory(&self) -> Option<Trajectory> {
+        let GameEntities {
+            balls,
+            paddle,
+            bricks,
+            ..
+        } = &self.entities;
+        let paddle = paddle.as_ref()?;
+        let view = FieldView {
+            balls,
+            paddle,
+            bricks,
+            size: self.size,
+            step_seconds: self.rules.timing.step_seconds(),
+        };
+
+        if let Some(held) = self.held_balls.first() {
+            let center = balls.get(held.index)?.position + Vector::new(0.5, 0.5);
+            let direction = deflection(paddle_offset(center.x, paddle));
+
+            return Some(Trajectory::trace(center, direction, &view));
+        }
+
+        predict_trajectory(&view)
+    }
+
+    /// A round is being played as long as there is a ball on the field.
+    pub fn is_playing(&self) -> bool {
+        !self.entities.balls.is_empty()
+    }
+
+    /// Whether a ball is resting on the paddle waiting to be launched.
+    pub fn is_ball_held(&self) -> bool {
+        !self.held_balls.is_empty()
+    }
+
+    /// Start a round on `level` with the ball resting on the middle of the
+    /// paddle, keeping what is left of the bricks when the level hasn't
+    /// changed.
+    pub fn reset(&mut self, level: u8) {
+        let game_width = self.size.x;
+        let game_height = self.size.y;
+        let difficulty = Difficulty::for_level(level);
+        let ball_speed = self.rules.timing.ball_speed * difficulty.ball_speed_scale;
+
+        if self.level != level {
+            self.entities.bricks.clear();
+        }
+
+        self.level = level;
+        self.rules.difficulty = difficulty;
+        self.ball_speed = ball_speed;
+        self.entities.capsules.clear();
+        self.entities.projectiles.clear();
+        self.effects.clear();
+        self.laser_cooldown = 0;
+        self.ticks_since_advance = 0;
+        self.hits_since_advance = 0;
+        self.combo = 0;
+
+        let paddle_size = Vector::new(difficulty.paddle_width as f32, 2.0);
+        let paddle_position = Vector::new(
+            (game_width / 2.0).floor() - (paddle_size.x / 2.0).floor(),
+            game_height - paddle_size.y,
+        );
+        let paddle = Entity::new(paddle_position, paddle_size, '=', EntityColor::Reset, 1);
+        self.entities.paddle = Some(paddle);
+
+        let ball_offset = (paddle_size.x / 2.0).floor();
+        let ball_size = Vector::new(1.0, 1.0);
+        let ball_position = Vector::new(
+            paddle_position.x + ball_offset,
+            paddle_position.y - ball_size.y,
+        );
+        let mut ball = Entity::new(ball_position, ball_size, '*', EntityColor::Reset, 1);
+        ball.apply_force(Vector::new(0.0, -ball_speed));
+        self.entities.balls = vec![ball];
+        self.held_balls = vec![HeldBall {
+            index: 0,
+            offset: ball_offset,
+        }];
+
+        // indestructible bricks outlive the level, so only required ones count
+        if !self.entities.bricks.iter().any(Entity::is_required) {
+            self.entities.bricks = match self.levels.len() {
+                0 => LevelLayout::classic().build_bricks(game_width as i32, &difficulty),
+                count => self.levels[(level.max(1) as usize - 1) % count]
+                    .build_bricks(game_width as i32, &difficulty),
+            };
+            self.level_ticks = 0;
+        }
+    }
+
+    /// Start `level` over from its full set of bricks.
+    pub fn restart(&mut self, level: u8) {
+        self.entities.bricks.clear();
+        self.reset(level);
+    }
+
+    pub fn step(&mut self, input: Input) -> Vec<GameEvent> {
+        let mut events = vec![];
+        let game_size = self.size;
+        let rules = self.rules;
+        let step_seconds = rules.timing.step_seconds();
+        let GameEntities {
+            balls,
+            paddle,
+            bricks,
+            capsules,
+            projectiles,
+            rng,
+        } = &mut self.entities;
+        let Some(paddle) = paddle else {
+            return events;
+        };
+
+        if balls.is_empty() {
+            return events;
+        }
+
+        let view = FieldView {
+            balls,
+            paddle,
+            bricks,
+            size: game_size,
+            step_seconds,
+        };
+        let steer = self.controller.steer(input, &view, rng);
+        let input = steer.input;
+
+        paddle.apply_force(Vector::new(input.paddle_force as f32 * PADDLE_PUSH, 0.0));
+
+        if let Some(target) = input.paddle_target {
+            paddle.velocity.x = 0.0;
+            paddle.position.x = target as f32 - (paddle.size.x / 2.0).floor();
+        }
+
+        if let Some(x) = steer.follow {
+            let paddle_step = AUTOMATION_PADDLE_SPEED * step_seconds;
+            let center = paddle.position.x + paddle.size.x / 2.0;
+
+            paddle.position.x += (x - center).clamp(-paddle_step, paddle_step);
+        }
+
+        paddle.update(game_size, step_seconds);
+
+        // the clock for the time bonus only runs while the ball is in play
+        if self.held_balls.is_empty() {
+            self.level_ticks += 1;
+        }
+
+        // a held ball takes the action before the laser does
+        let launching = !self.held_balls.is_empty() && (input.action || rules.automation_mode);
+        let mut hit_paddle = false;
+
+        for (index, ball) in balls.iter_mut().enumerate() {
+            if let Some(held) = self.held_balls.iter().find(|held| held.index == index) {
+                ball.position = Vector::new(
+                    paddle.position.x + held.offset.min(paddle.size.x - 1.0),
+                    paddle.position.y - ball.size.y,
+                );
+
+                if launching {
+                    let center = ball.position.x + 0.5;
+                    ball.velocity =
+                        paddle_bounce(center, paddle, &rules, rng) * ball.velocity.length();
+                }
+
+                continue;
+            }
+
+            let ball_hit_paddle =
+                move_ball(ball, paddle, bricks, game_size, &rules, rng, &mut events);
+
+            if ball_hit_paddle && self.effects.is_active(PowerUpKind::StickyPaddle) {
+                self.held_balls.push(HeldBall {
+                    index,
+                    offset: ball.position.x - paddle.position.x,
+                });
+            }
+
+            if ball_hit_paddle {
+                events.push(GameEvent::PaddleHit);
+            }
+
+            hit_paddle |= ball_hit_paddle;
+        }
+
+        if launching {
+            self.held_balls.clear();
+        }
+
+        self.laser_cooldown = self.laser_cooldown.saturating_sub(1);
+
+        let action_used = input.action && !launching;
+
+        if action_used && self.effects.is_active(PowerUpKind::Laser) && self.laser_cooldown == 0 {
+            projectiles.extend(fire_laser(paddle));
+            self.laser_cooldown = (LASER_COOLDOWN_SECONDS * rules.timing.tick_rate as f32) as u32;
+        }
+
+        for shot in projectiles.iter_mut() {
+            move_projectile(shot, bricks, step_seconds, &mut events);
+        }
+
+        projectiles.retain(|shot| shot.is_alive);
+        explode_bricks(bricks, &mut events);
+
+        let regenerate_ticks = (REGENERATE_SECONDS * rules.timing.tick_rate as f32) as u32;
+
+        for brick in bricks.iter_mut() {
+            brick.ticks_since_hit = brick.ticks_since_hit.saturating_add(1);
+
+            if brick.kind == BrickKind::Regenerating
+                && brick.health > 0
+                && brick.ticks_since_hit >= regenerate_ticks
+            {
+                brick.health = brick.original_health;
+            }
+        }
+
+        move_bricks(bricks, game_size, step_seconds);
+
+        let mut split = false;
+
+        for brick in bricks.iter().filter(|brick| brick.health == 0) {
+            split |= brick.kind == BrickKind::MultiBall;
+
+            if rng.random_bool(DROP_CHANCE) {
+                capsules.push(Capsule::dropped_from(brick, PowerUpKind::random(rng)));
+            }
+        }
+
+        bricks.retain(|brick| brick.health > 0);
+
+        let score_multiplier = self.effects.score_multiplier();
+        let mut row_bonuses: Vec<Score> = vec![];
+
+        for event in events.iter_mut() {
+            let GameEvent::Scored(score) = event else {
+                continue;
+            };
+
+            // each brick broken since the paddle was last touched raises the combo
+            self.combo += 1;
+            score.multiply(self.combo.min(MAX_COMBO) * score_multiplier);
+
+            let row = score.position.y;
+            let row_cleared = !bricks.iter().any(|brick| {
+                brick.is_required()
+                    && brick.position.y < row
+                    && row < brick.position.y + brick.size.y
+            });
+
+            if row_cleared && !row_bonuses.iter().any(|bonus| bonus.position.y == row) {
+                row_bonuses.push(Score {
+                    points: ROW_BONUS * score_multiplier,
+                    multiplier: score_multiplier,
+                    position: Vector::new(game_size.x / 2.0, row),
+                });
+            }
+        }
+
+        events.extend(row_bonuses.into_iter().map(GameEvent::Scored));
+
+        // the bricks come down after the row bonuses, which find the row of
+        // a broken brick by where it was when it broke
+        if let Some(advance) = rules.advance
+            && self.held_balls.is_empty()
+        {
+            self.ticks_since_advance += 1;
+            self.hits_since_advance += hit_paddle as u32;
+
+            let due = match advance {
+                Advance::Seconds(seconds) => {
+                    self.ticks_since_advance as f32 >= seconds * rules.timing.tick_rate as f32
+                }
+                Advance::PaddleHits(hits) => self.hits_since_advance >= hits,
+            };
+
+            if due {
+                self.ticks_since_advance = 0;
+                self.hits_since_advance = 0;
+
+                for brick in bricks.iter_mut() {
+                    brick.position.y += 1.0;
+                }
+            }
+        }
+
+        if hit_paddle {
+            self.combo = 0;
+
+            let max_speed =
+                rules.timing.ball_speed * rules.difficulty.ball_speed_scale * MAX_BALL_SPEED_UP;
+            self.ball_speed = (self.ball_speed * BALL_SPEED_UP).min(max_speed);
+        }
+
+        for capsule in capsules.iter_mut() {
+            capsule.entity.apply_velocity(step_seconds);
+
+            if capsule.overlaps(paddle) {
+                capsule.entity.is_alive = false;
+                events.push(GameEvent::PowerUp(capsule.kind));
+                split |= capsule.kind == PowerUpKind::MultiBall;
+
+                if let Some(seconds) = capsule.kind.duration_seconds() {
+                    let ticks = (seconds * rules.timing.tick_rate as f32) as u32;
+                    self.effects.activate(capsule.kind, ticks);
+                }
+            } else if capsule.entity.position.y > game_size.y {
+                capsule.entity.is_alive = false;
+            }
+        }
+
+        capsules.retain(|capsule| capsule.entity.is_alive);
+
+        if split {
+            split_balls(balls);
+        }
+
+        let ball_speed = self.ball_speed * self.effects.ball_speed_scale();
+
+        for ball in balls.iter_mut() {
+            ball.velocity = ball.velocity.with_length(ball_speed);
+
+            if ball.position.y > game_size.y {
+                ball.is_alive = false;
+                events.push(GameEvent::BallLost);
+            }
+        }
+
+        for held in self.held_balls.iter_mut() {
+            // lost balls before a held one shift it down the list
+            held.index -= balls[..held.index]
+                .iter()
+                .filter(|ball| !ball.is_alive)
+                .count();
+        }
+
+        balls.retain(|ball| ball.is_alive);
+        self.effects.tick();
+
+        resize_paddle(
+            paddle,
+            rules.difficulty.paddle_width as f32 * self.effects.paddle_scale(),
+            game_size,
+        );
+
+        let ball_is_alive = !balls.is_empty();
+        let level_cleared = !bricks.iter().any(Entity::is_required);
+        let overrun = bricks
+            .iter()
+            .any(|brick| brick.position.y + brick.size.y > paddle.position.y);
+
+        if overrun {
+            self.entities.balls.clear();
+            self.entities.bricks.clear();
+
+            if rules.endless {
+                self.reset(self.level);
+            } else {
+                events.push(GameEvent::Overrun);
+            }
+        } else if level_cleared && ball_is_alive {
+            self.entities.balls.clear();
+
+            if rules.endless {
+                self.reset(self.level);
+            } else {
+                events.push(GameEvent::LevelCleared(self.level));
+            }
+        } else if !ball_is_alive {
+            events.push(GameEvent::LostLife);
+
+            if rules.endless {
+                self.reset(self.level);
+            }
+        }
+
+        events
+    }
+}
+
+/// The direction a ball leaves the paddle in when it touches it at column
+/// `x`, straight up from the middle and angled further out towards the edges.
+fn paddle_bounce(x: f32, paddle: &Entity, rules: &Rules, rng: &mut ChaCha8Rng) -> Vector {
+    let mut offset = paddle_offset(x, paddle);
+    let jitter = rules.deflection_jitter;
+
+    if jitter > 0.0 {
+        offset = (offset + rng.random_range(-jitter..jitter)).clamp(-1.0, 1.0);
+    }
+
+    deflection(offset)
+}
+
+/// How far from the middle of the paddle column `x` is, from -1 on the left
+/// edge to 1 on the right edge.
+fn paddle_offset(x: f32, paddle: &Entity) -> f32 {
+    let half_width = paddle.size.x / 2.0;
+
+    ((x - (paddle.position.x + half_width)) / half_width).clamp(-1.0, 1.0)
+}
+
+/// The direction a ball leaves the paddle in when it touches it `offset`
+/// from the middle, from -1 on the left edge to 1 on the right edge.
+pub fn deflection(offset: f32) -> Vector {
+    let angle = offset.clamp(-1.0, 1.0) * MAX_DEFLECTION;
+
+    Vector::new(angle.sin(), -angle.cos())
+}
+
+/// A shot from each end of the paddle, heading straight up.
+fn fire_laser(paddle: &Entity) -> [Entity; 2] {
+    let y = paddle.position.y - 1.0;
+
+    [paddle.position.x, paddle.position.x + paddle.size.x - 1.0].map(|x| {
+        let mut shot = Entity::new(
+            Vector::new(x, y),
+            Vector::new(1.0, 1.0),
+            '|',
+            EntityColor::Reset,
+            1,
+        );
+        shot.velocity = Vector::new(0.0, -LASER_SPEED);
+        shot
+    })
+}
+
+/// Move a laser shot, damaging the first live brick in its path. The shot
+/// is spent when it hits a brick or leaves the top of the field.
+fn move_projectile(
+    shot: &mut Entity,
+    bricks: &mut [Entity],
+    step_seconds: f32,
+    events: &mut Vec<GameEvent>,
+) {
+    let origin = shot.position + Vector::new(0.5, 0.5);
+    let travel = shot.velocity * step_seconds;
+    let hit = bricks
+        .iter()
+        .enumerate()
+        .filter(|(_, brick)| brick.health > 0)
+        .filter_map(|(index, brick)| Some((index, sweep(origin, travel, brick)?)))
+        .min_by(|(_, a), (_, b)| a.time.total_cmp(&b.time));
+
+    if let Some((index, _)) = hit {
+        let brick = &mut bricks[index];
+
+        brick.lose_health();
+
+        if brick.health == 0 {
+            events.push(GameEvent::Scored(Score::brick(brick)));
+        }
+
+        shot.is_alive = false;
+        return;
+    }
+
+    shot.apply_velocity(step_seconds);
+
+    if shot.position.y < 0.0 {
+        shot.is_alive = false;
+    }
+}
+
+/// Grow `size` to the smallest field the game is played on.
+fn at_least_min_size(size: Vector) -> Vector {
+    Vector::new(size.x.max(MIN_FIELD_SIZE.x), size.y.max(MIN_FIELD_SIZE.y))
+}
+
+/// Slide the bricks of oscillating rows along, turning a row around when
+/// one of its bricks would leave the field.
+fn move_bricks(bricks: &mut [Entity], game_size: Vector, step_seconds: f32) {
+    let mut turned_rows: Vec<f32> = vec![];
+
+    for brick in bricks.iter_mut().filter(|brick| brick.velocity.x != 0.0) {
+        brick.apply_velocity(step_seconds);
+
+        if brick.position.x < 0.0 || brick.position.x + brick.size.x > game_size.x {
+            turned_rows.push(brick.position.y);
+        }
+    }
+
+    for brick in bricks
+        .iter_mut()
+        .filter(|brick| turned_rows.contains(&brick.position.y))
+    {
+        // step back inside before heading the other way
+        brick.apply_velocity(-step_seconds);
+        brick.velocity.x *= -1.0;
+    }
+}
+
+/// Set off every explosive brick destroyed this step, destroying the bricks
+/// around it and setting off any explosive bricks among them in turn.
+fn explode_bricks(bricks: &mut [Entity], events: &mut Vec<GameEvent>) {
+    let mut blasts = bricks
+        .iter()
+        .enumerate()
+        .filter(|(_, brick)| brick.health == 0 && brick.kind == BrickKind::Explosive)
+        .map(|(index, _)| index)
+        .collect::<Vec<_>>();
+
+    while let Some(index) = blasts.pop() {
+        let blast = bricks[index];
+        let left = blast.position.x - EXPLOSION_RADIUS;
+        let top = blast.position.y - EXPLOSION_RADIUS;
+        let right = blast.position.x + blast.size.x + EXPLOSION_RADIUS;
+        let bottom = blast.position.y + blast.size.y + EXPLOSION_RADIUS;
+
+        for (neighbour_index, neighbour) in bricks.iter_mut().enumerate() {
+            let in_reach = neighbour.position.x < right
+                && neighbour.position.x + neighbour.size.x > left
+                && neighbour.position.y < bottom
+                && neighbour.position.y + neighbour.size.y > top;
+
+            if !in_reach || neighbour.health == 0 || !neighbour.is_required() {
+                continue;
+            }
+
+            neighbour.health = 0;
+            events.push(GameEvent::Scored(Score::brick(neighbour)));
+
+            if neighbour.kind == BrickKind::Explosive {
+                blasts.push(neighbour_index);
+            }
+        }
+    }
+}
+
+/// Send two more balls off from every ball in play, angled either side of it.
+fn split_balls(balls: &mut Vec<Entity>) {
+    for index in 0..balls.len() {
+        for angle in [-SPLIT_ANGLE, SPLIT_ANGLE] {
+            if balls.len() >= MAX_BALLS {
+                return;
+            }
+
+            let mut ball = balls[index];
+            ball.velocity = ball.velocity.rotated(angle);
+            balls.push(ball);
+        }
+    }
+}
+
+/// Change the paddle's width to `width` while keeping it centered where it
+/// was and inside the field.
+fn resize_paddle(paddle: &mut Entity, width: f32, game_size: Vector) {
+    let width = width.round().max(2.0);
+
+    if paddle.size.x == width {
+        return;
+    }
+
+    let center = paddle.position.x + paddle.size.x / 2.0;
+    paddle.size.x = width;
+    paddle.position.x = (center - width / 2.0)
+        .round()
+        .clamp(0.0, (game_size.x - width).max(0.0));
+}
+
+/// Move the ball along its velocity, bouncing off the walls, paddle and
+/// bricks in the order the path reaches them rather than only checking where
+/// it ends up. Returns whether the paddle was hit.
+fn move_ball(
+    ball: &mut Entity,
+    paddle: &Entity,
+    bricks: &mut [Entity],
+    game_size: Vector,
+    rules: &Rules,
+    rng: &mut ChaCha8Rng,
+    events: &mut Vec<GameEvent>,
+) -> bool {
+    let half_cell = Vector::new(0.5, 0.5);
+    let mut origin = ball.position + half_cell;
+    let mut remaining = 1.0;
+    let mut hit_paddle = false;
+
+    for _ in 0..MAX_CONTACTS_PER_MOVE {
+        let travel = ball.velocity * (rules.timing.step_seconds() * remaining);
+        let paddle_contact = sweep(origin, travel, paddle).filter(|_| ball.velocity.y > 0.0);
+        let brick_contacts = bricks
+            .iter()
+            .enumerate()
+            .filter(|(_, brick)| brick.health > 0)
+            .filter_map(|(index, brick)| Some((index, sweep(origin, travel, brick)?)))
+            .collect::<Vec<_>>();
+        let first_brick_contact = brick_contacts
+            .iter()
+            .map(|(_, contact)| *contact)
+            .min_by(|a, b| a.time.total_cmp(&b.time));
+
+        let wall_contact = sweep_walls(origin, travel, game_size);
+
+        let Some((contact, target)) = [
+            (paddle_contact, Target::Paddle),
+            (first_brick_contact, Target::Bricks),
+            (wall_contact, Target::Wall),
+        ]
+        .into_iter()
+        .filter_map(|(contact, target)| Some((contact?, target)))
+        .min_by(|(a, _), (b, _)| a.time.total_cmp(&b.time)) else {
+            origin += travel;
+            break;
+        };
+
+        origin += travel * contact.time;
+        remaining *= 1.0 - contact.time;
+
+        if target == Target::Wall {
+            if contact.face.flips_x() {
+                ball.velocity.x *= -1.0;
+            }
+
+            if contact.face.flips_y() {
+                ball.velocity.y *= -1.0;
+            }
+
+            continue;
+        }
+
+        origin = back_off(origin, travel);
+
+        if target == Target::Paddle {
+            origin.y = paddle.position.y - 0.5;
+            hit_paddle = true;
+
+            ball.velocity = paddle_bounce(origin.x, paddle, rules, rng) * ball.velocity.length();
+            continue;
+        }
+
+        // every brick touched at the same moment takes a hit, so a ball
+        // landing on the seam between two bricks damages both
+        let mut flip_x = false;
+        let mut flip_y = false;
+        let mut inside = false;
+
+        for (index, brick_contact) in brick_contacts
+            .iter()
+            .filter(|(_, brick_contact)| brick_contact.is_simultaneous(&contact))
+        {
+            let brick = &mut bricks[*index];
+
+            brick.lose_health();
+
+            if brick.health == 0 {
+                events.push(GameEvent::Scored(Score::brick(brick)));
+            }
+
+            if brick_contact.face == Face::Inside {
+                inside = true;
+            } else {
+                flip_x |= brick_contact.face.flips_x();
+                flip_y |= brick_contact.face.flips_y();
+            }
+        }
+
+        // a brick that moved onto the ball takes a single hit, then the ball
+        // is dropped out underneath it and sent down so it can't be hit again
+        // on the next contact
+        if inside {
+            while let Some(brick) = bricks
+                .iter()
+                .find(|brick| brick.health > 0 && brick.is_point_inside(&origin))
+            {
+                origin.y = brick.position.y + brick.size.y;
+                ball.velocity.y = ball.velocity.y.abs();
+            }
+        }
+
+        if flip_x {
+            ball.velocity.x *= -1.0;
+        }
+
+        if flip_y {
+            ball.velocity.y *= -1.0;
+        }
+    }
+
+    ball.position = origin - half_cell;
+
+    hit_paddle
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Target {
+    Paddle,
+    Bricks,
+    Wall,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entity(x: f32, y: f32, width: f32, height: f32, health: usize) -> Entity {
+        Entity::new(
+            Vector::new(x, y),
+            Vector::new(width, height),
+            ' ',
+            EntityColor::Red,
+            health,
+        )
+    }
+
+    /// Move `ball` for a single step among `bricks`, with the paddle far
+    /// out of the way, returning the events it caused.
+    fn move_among(ball: &mut Entity, bricks: &mut [Entity]) -> Vec<GameEvent> {
+        let paddle = entity(0.0, 29.0, 10.0, 1.0, 1);
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        let mut events = vec![];
+
+        move_ball(
+            ball,
+            &paddle,
+            bricks,
+            Vector::new(40.0, 30.0),
+            &Rules::default(),
+            &mut rng,
+            &mut events,
+        );
+
+        events
+    }
+
+    /// A simulation on the classic bricks with the ball held on the paddle.
+    fn simulation() -> Simulation {
+        let mut simulation = Simulation::new(Vector::new(40.0, 20.0), vec![], 1, Timing::default());
+        simulation.reset(1);
+        simulation
+    }
+
+    /// Step `simulation` with `input` until one of the events `wanted` picks
+    /// out happens, returning the events of that step.
+    fn step_until(
+        simulation: &mut Simulation,
+        input: Input,
+        wanted: impl Fn(&GameEvent) -> bool,
+    ) -> Vec<GameEvent> {
+        // a minute is plenty for anything to happen on a field this small
+        for _ in 0..60 * simulation.timing().tick_rate {
+            let events = simulation.step(input);
+
+            if events.iter().any(&wanted) {
+                return events;
+            }
+        }
+
+        panic!("the event never happened");
+    }
+
+    fn launch() -> Input {
+        Input {
+            action: true,
+            ..Input::default()
+        }
+    }
+
+    #[test]
+    fn a_held_ball_waits_for_the_action_button() {
+        let mut simulation = simulation();
+
+        for _ in 0..30 {
+            assert_eq!(simulation.step(Input::default()), []);
+        }
+
+        assert!(simulation.is_ball_held());
+
+        simulation.step(launch());
+
+        assert!(!simulation.is_ball_held());
+        assert!(simulation.entities().balls[0].velocity.y < 0.0);
+    }
+
+    #[test]
+    fn hitting_a_brick_and_catching_the_ball_are_reported() {
+        let mut simulation = simulation();
+        let health = |simulation: &Simulation| {
+            simulation
+                .entities()
+                .bricks
+                .iter()
+                .map(|brick| brick.health)
+                .sum::<usize>()
+        };
+        let full_health = health(&simulation);
+
+        simulation.step(launch());
+
+        // the paddle hasn't moved, so the ball comes nearly straight back to it
+        let events = step_until(&mut simulation, Input::default(), |event| {
+            *event == GameEvent::PaddleHit
+        });
+
+        assert!(health(&simulation) < full_health);
+        assert!(!events.contains(&GameEvent::BallLost));
+        assert!(simulation.is_playing());
+    }
+
+    #[test]
+    fn missing_the_ball_loses_a_life() {
+        let mut simulation = simulation();
+
+        simulation.set_controller(ControllerKind::Mouse.build());
+        simulation.step(launch());
+
+        // keep the paddle in the far corner, away from the ball
+        let away = Input {
+            paddle_target: Some(0),
+            ..Input::default()
+        };
+        let events = step_until(&mut simulation, away, |event| *event == GameEvent::LostLife);
+
+        assert!(events.contains(&GameEvent::BallLost));
+        assert!(!simulation.is_playing());
+    }
+
+    #[test]
+    fn breaking_the_last_brick_clears_the_level() {
+        let mut simulation = simulation();
+
+        simulation.entities.bricks = vec![entity(0.0, 2.0, 40.0, 1.0, 1)];
+        simulation.step(launch());
+
+        let events = step_until(&mut simulation, Input::default(), |event| {
+            matches!(event, GameEvent::LevelCleared(_))
+        });
+
+        assert!(events.contains(&GameEvent::LevelCleared(1)));
+        assert!(
+            events
+                .iter()
+                .any(|event| matches!(event, GameEvent::Scored(_)))
+        );
+        assert!(!simulation.is_playing());
+    }
+
+    /// A simulation with `ball` already in play among `bricks`.
+    fn simulation_with(ball: Entity, bricks: Vec<Entity>) -> Simulation {
+        let mut simulation = simulation();
+
+        simulation.entities.balls = vec![ball];
+        simulation.entities.bricks = bricks;
+        simulation.held_balls.clear();
+        simulation
+    }
+
+    fn scores(events: &[GameEvent]) -> Vec<Score> {
+        events
+            .iter()
+            .filter_map(|event| match event {
+                GameEvent::Scored(score) => Some(*score),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// A ball heading straight up the seam at x = 4, just below a row of
+    /// bricks at y = 2.
+    fn ball_up_the_seam() -> Entity {
+        let mut ball = entity(3.5, 3.0, 1.0, 1.0, 1);
+        ball.velocity = Vector::new(0.0, -60.0);
+        ball
+    }
+
+    #[test]
+    fn bricks_broken_before_the_ball_comes_back_build_a_combo() {
+        let row = vec![
+            entity(0.0, 2.0, 4.0, 1.0, 1),
+            entity(4.0, 2.0, 4.0, 1.0, 1),
+            entity(8.0, 2.0, 4.0, 1.0, 1),
+        ];
+        let mut simulation = simulation_with(ball_up_the_seam(), row);
+
+        let scores = scores(&simulation.step(Input::default()));
+
+        assert_eq!(
+            scores
+                .iter()
+                .map(|score| (score.points, score.multiplier))
+                .collect::<Vec<_>>(),
+            [(1, 1), (2, 2)]
+        );
+
+        // catch the ball as it comes straight back down
+        if let Some(paddle) = &mut simulation.entities.paddle {
+            paddle.position.x = 0.0;
+        }
+
+        step_until(&mut simulation, Input::default(), |event| {
+            *event == GameEvent::PaddleHit
+        });
+
+        assert_eq!(simulation.combo, 0);
+    }
+
+    #[test]
+    fn breaking_the_last_brick_in_a_row_earns_a_bonus() {
+        let bricks = vec![
+            entity(0.0, 2.0, 4.0, 1.0, 1),
+            entity(4.0, 2.0, 4.0, 1.0, 1),
+            // a row of its own, out of the ball's way
+            entity(20.0, 0.0, 4.0, 1.0, 1),
+        ];
+        let mut simulation = simulation_with(ball_up_the_seam(), bricks);
+
+        let row_bonuses = scores(&simulation.step(Input::default()))
+            .into_iter()
+            .filter(|score| score.points == ROW_BONUS)
+            .count();
+
+        assert_eq!(row_bonuses, 1);
+    }
+
+    #[test]
+    fn bricks_coming_down_do_not_empty_the_row_they_left() {
+        let row = vec![
+            entity(0.0, 2.0, 4.0, 1.0, 1),
+            entity(4.0, 2.0, 4.0, 1.0, 1),
+            entity(8.0, 2.0, 4.0, 1.0, 1),
+        ];
+        let mut ball = entity(1.5, 3.0, 1.0, 1.0, 1);
+        ball.velocity = Vector::new(0.0, -60.0);
+        let mut simulation = simulation_with(ball, row);
+
+        // the bricks come down a row on every step
+        simulation.set_advance(Some(Advance::Seconds(1.0 / 60.0)));
+
+        let scores = scores(&simulation.step(Input::default()));
+
+        assert_eq!(scores.len(), 1);
+        assert_eq!(scores[0].points, 1);
+        assert!(
+            simulation
+                .entities()
+                .bricks
+                .iter()
+                .all(|brick| brick.position.y == 3.0)
+        );
+    }
+
+    #[test]
+    fn a_ball_up_the_seam_hits_both_bricks_and_bounces_once() {
+        let mut bricks = [entity(0.0, 0.0, 4.0, 1.0, 2), entity(4.0, 0.0, 4.0, 1.0, 2)];
+        // the middle of the ball runs straight up the seam at x = 4
+        let mut ball = entity(3.5, 1.0, 1.0, 1.0, 1);
+        ball.velocity = Vector::new(0.0, -60.0);
+
+        move_among(&mut ball, &mut bricks);
+
+        assert_eq!(bricks.map(|brick| brick.health), [1, 1]);
+        assert!(ball.velocity.y > 0.0);
+    }
+
+    #[test]
+    fn bricks_broken_together_are_each_scored() {
+        let mut bricks = [entity(0.0, 0.0, 4.0, 1.0, 1), entity(4.0, 0.0, 4.0, 1.0, 1)];
+        let mut ball = entity(3.5, 1.0, 1.0, 1.0, 1);
+        ball.velocity = Vector::new(0.0, -60.0);
+
+        let events = move_among(&mut ball, &mut bricks);
+        let scored = events
+            .iter()
+            .filter(|event| matches!(event, GameEvent::Scored(_)))
+            .count();
+
+        assert_eq!(scored, 2);
+    }
+
+    #[test]
+    fn a_brick_moving_onto_the_ball_is_hit_once() {
+        let mut bricks = [entity(8.0, 10.0, 6.0, 2.0, 5)];
+        // the brick has slid over the ball, which is still heading up
+        let mut ball = entity(10.0, 10.0, 1.0, 1.0, 1);
+        ball.velocity = Vector::new(3.0, -20.0);
+
+        move_among(&mut ball, &mut bricks);
+
+        assert_eq!(bricks[0].health, 4);
+        assert!(ball.velocity.y > 0.0);
+        assert!(ball.position.y + 0.5 >= 12.0);
+    }
+
+    #[test]
+    fn a_fast_ball_does_not_tunnel_through_a_brick() {
+        let mut bricks = [entity(0.0, 5.0, 8.0, 1.0, 1)];
+        let mut ball = entity(3.5, 15.0, 1.0, 1.0, 1);
+        // twenty cells in a single step
+        ball.velocity = Vector::new(0.0, -1200.0);
+
+        move_among(&mut ball, &mut bricks);
+
+        assert_eq!(bricks[0].health, 0);
+        assert!(ball.position.y > 6.0);
+    }
+}
@@ -0,0 +1,46 @@
+use std::time::Duration;
+
+/// Frames that took longer than this many ticks are let go rather than
+/// caught up on, so a stalled terminal doesn't make the game race ahead.
+const MAX_TICKS_PER_FRAME: u32 = 8;
+
+/// Turns the uneven time between frames into a steady number of simulation
+/// ticks.
+#[derive(Debug)]
+pub struct FixedTimestep {
+    step: Duration,
+    accumulator: Duration,
+}
+
+impl FixedTimestep {
+    pub fn new(tick_rate: u32) -> Self {
+        Self {
+            step: Duration::from_secs(1) / tick_rate.max(1),
+            accumulator: Duration::ZERO,
+        }
+    }
+
+    /// How many ticks to run now that `elapsed` more time has passed.
+    pub fn advance(&mut self, elapsed: Duration) -> u32 {
+        self.accumulator += elapsed;
+
+        let mut ticks = 0;
+
+        while self.accumulator >= self.step {
+            self.accumulator -= self.step;
+            ticks += 1;
+
+            if ticks == MAX_TICKS_PER_FRAME {
+                self.accumulator = Duration::ZERO;
+                break;
+            }
+        }
+
+        ticks
+    }
+
+    /// Forget any time left over, for when the clock should start fresh.
+    pub fn reset(&mut self) {
+        self.accumulator = Duration::ZERO;
+    }
+}
@@ -0,0 +1,171 @@
+use crate::game::{
+    collision::{back_off, sweep, sweep_walls},
+    controller::FieldView,
+    vector::Vector,
+};
+
+/// Bounces a path is followed through before giving up on it coming back
+/// down, a ball moving sideways would otherwise bounce between the walls
+/// forever.
+const MAX_TRACED_BOUNCES: usize = 16;
+
+/// The path a ball takes until it comes back down to the paddle.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Trajectory {
+    /// The middle of the ball where it starts and after every bounce, ending
+    /// where it lands when it does.
+    pub points: Vec<Vector>,
+    /// Where the middle of the ball meets the top of the paddle, none when it
+    /// doesn't come back down within the bounces followed.
+    pub landing: Option<Vector>,
+    /// Bricks the ball breaks on the way.
+    pub bricks_broken: usize,
+}
+
+impl Trajectory {
+    /// Follow the middle of a ball leaving `origin` in `direction`, bouncing
+    /// off the walls and the bricks in `view` and wearing the bricks down as
+    /// it goes.
+    pub fn trace(origin: Vector, direction: Vector, view: &FieldView<'_>) -> Self {
+        let mut health = view
+            .bricks
+            .iter()
+            .map(|brick| brick.health)
+            .collect::<Vec<_>>();
+        // far enough to cross the whole field in one go
+        let reach = view.size.x + view.size.y;
+        let paddle_top = view.paddle.position.y;
+        let mut trajectory = Self {
+            points: vec![origin],
+            ..Self::default()
+        };
+        let mut origin = origin;
+        let mut direction = direction;
+
+        for _ in 0..MAX_TRACED_BOUNCES {
+            let travel = direction.with_length(reach);
+            let brick_contact = view
+                .bricks
+                .iter()
+                .enumerate()
+                .filter(|(index, _)| health[*index] > 0)
+                .filter_map(|(index, brick)| Some((index, sweep(origin, travel, brick)?)))
+                .min_by(|(_, a), (_, b)| a.time.total_cmp(&b.time));
+            let wall_contact = sweep_walls(origin, travel, view.size);
+
+            let contact = match (brick_contact, wall_contact) {
+                (Some((index, brick)), Some(wall)) if brick.time <= wall.time => {
+                    Some((brick, Some(index)))
+                }
+                (_, Some(wall)) => Some((wall, None)),
+                (Some((index, brick)), None) => Some((brick, Some(index))),
+                (None, None) => None,
+            };
+            let landing_time =
+                (travel.y > 0.0).then(|| ((paddle_top - origin.y) / travel.y).max(0.0));
+
+            if let Some(time) = landing_time
+                && contact.is_none_or(|(contact, _)| time <= contact.time)
+            {
+                let landing = origin + travel * time;
+
+                trajectory.points.push(landing);
+                trajectory.landing = Some(landing);
+                break;
+            }
+
+            // heading up or sideways with nothing in the way
+            let Some((contact, brick_index)) = contact else {
+                break;
+            };
+
+            origin += travel * contact.time;
+
+            if let Some(index) = brick_index {
+                origin = back_off(origin, travel);
+
+                if view.bricks[index].is_required() {
+                    health[index] -= 1;
+                    trajectory.bricks_broken += (health[index] == 0) as usize;
+                }
+            }
+
+            trajectory.points.push(origin);
+
+            if contact.face.flips_x() {
+                direction.x *= -1.0;
+            }
+
+            if contact.face.flips_y() {
+                direction.y *= -1.0;
+            }
+        }
+
+        trajectory
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::entity::{Entity, EntityColor};
+
+    fn entity(x: f32, y: f32, width: f32, height: f32, health: usize) -> Entity {
+        Entity::new(
+            Vector::new(x, y),
+            Vector::new(width, height),
+            ' ',
+            EntityColor::Red,
+            health,
+        )
+    }
+
+    /// Trace a path among `bricks` on a 40 by 22 field with the top of the
+    /// paddle at y = 20.
+    fn trace(origin: Vector, direction: Vector, bricks: &[Entity]) -> Trajectory {
+        let paddle = entity(15.0, 20.0, 10.0, 2.0, 1);
+        let view = FieldView {
+            balls: &[],
+            paddle: &paddle,
+            bricks,
+            size: Vector::new(40.0, 22.0),
+            step_seconds: 1.0 / 60.0,
+        };
+
+        Trajectory::trace(origin, direction, &view)
+    }
+
+    #[test]
+    fn a_ball_moving_sideways_is_given_up_on() {
+        let trajectory = trace(Vector::new(10.5, 10.5), Vector::new(1.0, 0.0), &[]);
+
+        assert_eq!(trajectory.landing, None);
+        // the start and a point for every wall bounced off
+        assert_eq!(trajectory.points.len(), MAX_TRACED_BOUNCES + 1);
+        assert!(trajectory.points.iter().all(|point| point.y == 10.5));
+    }
+
+    #[test]
+    fn a_path_bounces_off_the_first_brick_it_meets() {
+        let bricks = [
+            entity(20.0, 8.0, 1.0, 1.0, 1),
+            entity(20.0, 4.0, 1.0, 1.0, 1),
+        ];
+        let trajectory = trace(Vector::new(20.5, 19.5), Vector::new(0.0, -1.0), &bricks);
+
+        assert_eq!(trajectory.points.len(), 3);
+        assert!((trajectory.points[1].y - 9.0).abs() < 0.01);
+        assert_eq!(trajectory.landing, Some(Vector::new(20.5, 20.0)));
+        assert_eq!(trajectory.bricks_broken, 1);
+    }
+
+    #[test]
+    fn a_brick_with_health_to_spare_is_bounced_off_but_not_broken() {
+        let bricks = [entity(20.0, 4.0, 1.0, 1.0, 2)];
+        let trajectory = trace(Vector::new(20.5, 19.5), Vector::new(0.0, -1.0), &bricks);
+
+        assert!(trajectory.points.iter().all(|point| point.y >= 4.99));
+        assert!(trajectory.landing.is_some());
+        assert_eq!(trajectory.bricks_broken, 0);
+    }
+}
@@ -1,19 +1,47 @@
-use std::ops::{Add, AddAssign, Sub};
+use std::ops::{Add, AddAssign, Mul, Sub};
 
-#[derive(Clone, Copy, Debug)]
+/// A position or movement in field space, where every cell is one unit.
+/// Values are fractional so the ball can move smoothly between cells,
+/// drawing rounds them to the nearest cell.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
 pub struct Vector {
-    pub x: i32,
-    pub y: i32,
+    pub x: f32,
+    pub y: f32,
 }
 
 impl Vector {
     pub fn zero() -> Self {
-        Self { x: 0, y: 0 }
+        Self { x: 0.0, y: 0.0 }
     }
 
-    pub fn new(x: i32, y: i32) -> Self {
+    pub fn new(x: f32, y: f32) -> Self {
         Self { x, y }
     }
+
+    pub fn length(&self) -> f32 {
+        (self.x * self.x + self.y * self.y).sqrt()
+    }
+
+    /// The same length turned by `angle` radians.
+    pub fn rotated(&self, angle: f32) -> Self {
+        let (sin, cos) = angle.sin_cos();
+
+        Self {
+            x: self.x * cos - self.y * sin,
+            y: self.x * sin + self.y * cos,
+        }
+    }
+
+    /// The same direction with a length of `length`.
+    pub fn with_length(&self, length: f32) -> Self {
+        let current = self.length();
+
+        if current == 0.0 {
+            return *self;
+        }
+
+        *self * (length / current)
+    }
 }
 
 impl AddAssign for Vector {
@@ -26,8 +54,8 @@ impl AddAssign for Vector {
 impl From<(i64, i64)> for Vector {
     fn from((x, y): (i64, i64)) -> Self {
         Self {
-            x: x as i32,
-            y: y as i32,
+            x: x as f32,
+            y: y as f32,
         }
     }
 }
@@ -53,3 +81,14 @@ impl Sub for Vector {
         }
     }
 }
+
+impl Mul<f32> for Vector {
+    type Output = Self;
+
+    fn mul(self, rhs: f32) -> Self::Output {
+        Self {
+            x: self.x * rhs,
+            y: self.y * rhs,
+        }
+    }
+}
@@ -0,0 +1,335 @@
+use std::{
+    cmp::Reverse,
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+use eyre::{Result, WrapErr, bail, eyre};
+
+use crate::game::simulation::Advance;
+
+const HEADER: &str = "# anathema breakout high scores";
+const DATA_DIRECTORY: &str = "anathema-breakout";
+const HIGH_SCORE_FILE: &str = "highscores";
+/// Scores kept for every game mode.
+pub const TABLE_SIZE: usize = 10;
+/// Longest name that can be entered for a high score.
+pub const MAX_NAME_LENGTH: usize = 12;
+
+/// The rules a game was played under, each keeps its own table.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum GameMode {
+    #[default]
+    Classic,
+    /// The bricks come down over time.
+    Survival,
+}
+
+impl GameMode {
+    pub fn for_advance(advance: Option<Advance>) -> Self {
+        Self::for_survival(advance.is_some())
+    }
+
+    pub fn for_survival(survival: bool) -> Self {
+        if survival {
+            GameMode::Survival
+        } else {
+            GameMode::Classic
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GameMode::Classic => "classic",
+            GameMode::Survival => "survival",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "classic" => Some(GameMode::Classic),
+            "survival" => Some(GameMode::Survival),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HighScore {
+    pub name: String,
+    pub score: u32,
+    pub mode: GameMode,
+    /// The seed the game started with, so it can be played again.
+    pub seed: u64,
+}
+
+/// The best scores for every game mode, saved as text with one score per
+/// line:
+///
+/// ```text
+/// # anathema breakout high scores
+/// classic 1200 98765 ada
+/// survival 340 1234 grace
+/// ```
+#[derive(Debug, Default)]
+pub struct HighScores {
+    path: Option<PathBuf>,
+    entries: Vec<HighScore>,
+}
+
+impl HighScores {
+    /// Load the table from the user's data directory, an empty table when
+    /// there is none yet.
+    pub fn load() -> Result<Self> {
+        let Some(path) = high_score_path() else {
+            return Ok(Self::default());
+        };
+
+        if !path.exists() {
+            return Ok(Self {
+                path: Some(path),
+                entries: vec![],
+            });
+        }
+
+        let source = fs::read_to_string(&path)
+            .wrap_err_with(|| format!("could not read high scores {}", path.display()))?;
+        let entries = parse(&source, &path)?;
+
+        Ok(Self {
+            path: Some(path),
+            entries,
+        })
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let Some(path) = &self.path else {
+            bail!("no data directory to save high scores in, set HOME or XDG_DATA_HOME");
+        };
+
+        if let Some(directory) = path.parent() {
+            fs::create_dir_all(directory)
+                .wrap_err_with(|| format!("could not create {}", directory.display()))?;
+        }
+
+        fs::write(path, self.to_text())
+            .wrap_err_with(|| format!("could not write high scores {}", path.display()))
+    }
+
+    /// The best scores for `mode`, highest first.
+    pub fn top(&self, mode: GameMode) -> impl Iterator<Item = &HighScore> {
+        self.entries.iter().filter(move |entry| entry.mode == mode)
+    }
+
+    /// Whether `score` would make it onto the table for `mode`.
+    pub fn qualifies(&self, mode: GameMode, score: u32) -> bool {
+        score > 0
+            && (self.top(mode).count() < TABLE_SIZE
+                || self.top(mode).any(|entry| entry.score < score))
+    }
+
+    /// Add `entry` to its table, dropping whatever falls off the end.
+    pub fn insert(&mut self, entry: HighScore) {
+        let mode = entry.mode;
+
+        self.entries.push(entry);
+        // stable, so earlier scores stay ahead of later ties
+        self.entries.sort_by_key(|entry| Reverse(entry.score));
+
+        let mut kept = 0;
+        self.entries.retain(|entry| {
+            if entry.mode != mode {
+                return true;
+            }
+
+            kept += 1;
+            kept <= TABLE_SIZE
+        });
+    }
+
+    /// The table for `mode` as rows ready to show, e.g. ` 1. ada  1200  seed 98765`.
+    pub fn rows(&self, mode: GameMode) -> Vec<String> {
+        self.top(mode)
+            .enumerate()
+            .map(|(index, entry)| {
+                format!(
+                    "{:>2}. {:<width$}  {:>6}  seed {}",
+                    index + 1,
+                    entry.name,
+                    entry.score,
+                    entry.seed,
+                    width = MAX_NAME_LENGTH
+                )
+            })
+            .collect()
+    }
+
+    fn to_text(&self) -> String {
+        let mut lines = vec![HEADER.to_owned()];
+
+        for entry in &self.entries {
+            lines.push(format!(
+                "{} {} {} {}",
+                entry.mode.as_str(),
+                entry.score,
+                entry.seed,
+                entry.name
+            ));
+        }
+
+        lines.join("\n") + "\n"
+    }
+}
+
+fn parse(source: &str, path: &Path) -> Result<Vec<HighScore>> {
+    let mut entries = vec![];
+
+    for (index, line) in source.lines().enumerate() {
+        let location = format!("{}:{}", path.display(), index + 1);
+        let trimmed = line.trim();
+
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+
+        // the name is whatever is left, spaces and all
+        let mut fields = trimmed.splitn(4, ' ');
+        let (Some(mode), Some(score), Some(seed), Some(name)) =
+            (fields.next(), fields.next(), fields.next(), fields.next())
+        else {
+            bail!("{location}: expected `mode score seed name`");
+        };
+
+        entries.push(HighScore {
+            name: name.to_owned(),
+            score: score
+                .parse()
+                .map_err(|_| eyre!("{location}: expected a score, found `{score}`"))?,
+            mode: GameMode::parse(mode)
+                .ok_or_else(|| eyre!("{location}: unknown game mode `{mode}`"))?,
+            seed: seed
+                .parse()
+                .map_err(|_| eyre!("{location}: expected a seed, found `{seed}`"))?,
+        });
+    }
+
+    entries.sort_by_key(|entry| Reverse(entry.score));
+
+    Ok(entries)
+}
+
+/// `$XDG_DATA_HOME/anathema-breakout/highscores`, falling back on
+/// `~/.local/share` when `XDG_DATA_HOME` isn't set.
+fn high_score_path() -> Option<PathBuf> {
+    let data_home = env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .filter(|path| path.is_absolute())
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")))?;
+
+    Some(data_home.join(DATA_DIRECTORY).join(HIGH_SCORE_FILE))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(name: &str, score: u32, mode: GameMode) -> HighScore {
+        HighScore {
+            name: name.to_owned(),
+            score,
+            mode,
+            seed: 7,
+        }
+    }
+
+    #[test]
+    fn parse_keeps_spaces_in_names_and_sorts_by_score() {
+        let source = "# anathema breakout high scores\n\
+                      classic 300 11 ada lovelace\n\
+                      \n\
+                      survival 50 12 grace\n\
+                      classic 900 13 alan\n";
+        let entries = parse(source, Path::new("highscores")).unwrap();
+
+        assert_eq!(
+            entries
+                .iter()
+                .map(|entry| entry.name.as_str())
+                .collect::<Vec<_>>(),
+            ["alan", "ada lovelace", "grace"]
+        );
+        assert_eq!(entries[1].seed, 11);
+        assert_eq!(entries[2].mode, GameMode::Survival);
+    }
+
+    #[test]
+    fn parse_reports_bad_lines_with_their_line() {
+        let error = parse(
+            "classic 300 11 ada\nclassic lots 12 grace\n",
+            Path::new("hs"),
+        )
+        .unwrap_err()
+        .to_string();
+        assert_eq!(error, "hs:2: expected a score, found `lots`");
+
+        let error = parse("arcade 300 11 ada\n", Path::new("hs"))
+            .unwrap_err()
+            .to_string();
+        assert_eq!(error, "hs:1: unknown game mode `arcade`");
+    }
+
+    #[test]
+    fn insert_trims_only_the_table_it_adds_to() {
+        let mut scores = HighScores::default();
+
+        scores.insert(entry("survivor", 1, GameMode::Survival));
+
+        for score in 1..=TABLE_SIZE as u32 {
+            scores.insert(entry("player", score * 10, GameMode::Classic));
+        }
+
+        scores.insert(entry("best", 1000, GameMode::Classic));
+
+        let classic = scores
+            .top(GameMode::Classic)
+            .map(|entry| entry.score)
+            .collect::<Vec<_>>();
+
+        assert_eq!(classic.len(), TABLE_SIZE);
+        assert_eq!(classic.first(), Some(&1000));
+        // the lowest score fell off the end
+        assert_eq!(classic.last(), Some(&20));
+        assert_eq!(scores.top(GameMode::Survival).count(), 1);
+    }
+
+    #[test]
+    fn ties_keep_the_earlier_score_ahead() {
+        let mut scores = HighScores::default();
+
+        scores.insert(entry("first", 100, GameMode::Classic));
+        scores.insert(entry("second", 100, GameMode::Classic));
+
+        let names = scores
+            .top(GameMode::Classic)
+            .map(|entry| entry.name.as_str())
+            .collect::<Vec<_>>();
+
+        assert_eq!(names, ["first", "second"]);
+    }
+
+    #[test]
+    fn a_full_table_only_takes_scores_that_beat_it() {
+        let mut scores = HighScores::default();
+
+        assert!(!scores.qualifies(GameMode::Classic, 0));
+        assert!(scores.qualifies(GameMode::Classic, 1));
+
+        for _ in 0..TABLE_SIZE {
+            scores.insert(entry("player", 50, GameMode::Classic));
+        }
+
+        assert!(!scores.qualifies(GameMode::Classic, 50));
+        assert!(scores.qualifies(GameMode::Classic, 51));
+        assert!(scores.qualifies(GameMode::Survival, 1));
+    }
+}
@@ -1,15 +1,54 @@
 mod app;
 mod game;
+mod highscores;
+mod options;
 mod scenes;
+mod terminal;
 
-use crate::{app::App, game::Game};
+use std::path::Path;
+
+use crate::{
+    app::App,
+    game::{Game, bench::Bench, controller::ControllerKind, level::load_levels, replay::Replay},
+    highscores::GameMode,
+};
 use anathema::{
     prelude::{Backend, Document, TuiBackend},
     runtime::Runtime,
 };
-use bb_anathema_components::BBAppComponent;
+use eyre::WrapErr;
+
+pub use options::Options;
+pub use terminal::install_hooks;
+
+pub fn run(options: Options) -> eyre::Result<()> {
+    let levels = load_levels(Path::new("levels"))?;
 
-pub fn run() -> eyre::Result<()> {
+    if let Some(games) = options.bench_games {
+        let bench = Bench {
+            controller: options
+                .bench_controller
+                .unwrap_or(ControllerKind::Predictive),
+            timing: options.timing,
+            advance: options.advance,
+            seed: options.seed.unwrap_or_default(),
+        };
+        let report = bench.run(&levels, games);
+
+        if options.bench_json {
+            println!("{}", report.json());
+        } else {
+            println!("{}", report.table());
+        }
+
+        return Ok(());
+    }
+
+    let replay = options.replay.as_deref().map(Replay::load).transpose()?;
+    let seed = replay.as_ref().map(|replay| replay.seed).or(options.seed);
+    let advance = replay
+        .as_ref()
+        .map_or(options.advance, |replay| replay.advance);
     let doc = Document::new("@App");
     let mut backend = TuiBackend::builder()
         .enable_alt_screen()
@@ -17,20 +56,22 @@ pub fn run() -> eyre::Result<()> {
         .enable_raw_mode()
         .hide_cursor()
         .finish()
-        .unwrap();
+        .wrap_err("could not set up the terminal")?;
 
     backend.finalize();
 
     let mut builder = Runtime::builder(doc, &backend);
 
     bb_anathema_components::register_all(&mut builder)?;
-    App::register_to(&mut builder)?;
+    App::new(seed, replay.is_some(), GameMode::for_advance(advance)).register_to(&mut builder)?;
     scenes::register_scenes(&mut builder)?;
-    Game::register_to(&mut builder)?;
+    Game::new(levels, options.timing, options.advance, replay).register_to(&mut builder)?;
+
+    let result = builder.finish(&mut backend, |runtime, backend| runtime.run(backend));
 
-    builder
-        .finish(&mut backend, |runtime, backend| runtime.run(backend))
-        .unwrap();
+    // whether the game was quit or failed, the terminal is handed back intact
+    terminal::restore().wrap_err("could not restore the terminal")?;
+    result?;
 
     Ok(())
 }
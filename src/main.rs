@@ -1,9 +1,10 @@
-use anathema_breakout::run;
+use anathema_breakout::{Options, install_hooks, run};
 use eyre::Result;
 
 fn main() -> Result<()> {
-    color_eyre::install()?;
-    run()?;
+    install_hooks()?;
+    let options = Options::from_args(std::env::args().skip(1))?;
+    run(options)?;
 
     Ok(())
 }
@@ -0,0 +1,140 @@
+use std::path::PathBuf;
+
+use eyre::{Result, bail, eyre};
+
+use crate::{
+    app::MAX_SEED,
+    game::{
+        controller::ControllerKind,
+        simulation::{Advance, Timing},
+    },
+};
+
+/// How often the bricks come down in `--survival`.
+const SURVIVAL_SECONDS: f32 = 10.0;
+
+/// Settings chosen on the command line.
+#[derive(Debug, Default)]
+pub struct Options {
+    /// How often the simulation steps and how fast the ball starts.
+    pub timing: Timing,
+    /// Survival mode, bringing the bricks down a row at a time.
+    pub advance: Option<Advance>,
+    /// Play every game with this seed instead of a random one.
+    pub seed: Option<u64>,
+    /// Watch a recorded game instead of playing.
+    pub replay: Option<PathBuf>,
+    /// Play this many games without a terminal and print how the controller
+    /// did instead of starting the game.
+    pub bench_games: Option<u32>,
+    /// The controller `--bench-ai` plays with.
+    pub bench_controller: Option<ControllerKind>,
+    /// Print the `--bench-ai` results as JSON instead of a table.
+    pub bench_json: bool,
+}
+
+impl Options {
+    pub fn from_args(args: impl IntoIterator<Item = String>) -> Result<Self> {
+        let mut options = Self::default();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--seed" => {
+                    let Some(seed) = args.next() else {
+                        bail!("--seed needs a number after it");
+                    };
+                    let seed = seed
+                        .parse()
+                        .ok()
+                        .filter(|seed| *seed <= MAX_SEED)
+                        .ok_or_else(|| {
+                            eyre!("--seed expects a number up to {MAX_SEED}, found `{seed}`")
+                        })?;
+
+                    options.seed = Some(seed);
+                }
+                "--tick-rate" => {
+                    let Some(rate) = args.next() else {
+                        bail!("--tick-rate needs the number of ticks per second after it");
+                    };
+                    let rate = rate.parse().ok().filter(|rate| *rate > 0).ok_or_else(|| {
+                        eyre!("--tick-rate expects a positive number, found `{rate}`")
+                    })?;
+
+                    options.timing.tick_rate = rate;
+                }
+                "--ball-speed" => {
+                    let Some(speed) = args.next() else {
+                        bail!("--ball-speed needs the number of cells per second after it");
+                    };
+                    let speed = speed
+                        .parse::<f32>()
+                        .ok()
+                        .filter(|speed| *speed > 0.0 && speed.is_finite())
+                        .ok_or_else(|| {
+                            eyre!("--ball-speed expects a positive number, found `{speed}`")
+                        })?;
+
+                    options.timing.ball_speed = speed;
+                }
+                "--advance" => {
+                    let Some(value) = args.next() else {
+                        bail!("--advance needs a value like `10s` or `6hits` after it");
+                    };
+                    let advance = Advance::parse(&value).ok_or_else(|| {
+                        eyre!("--advance expects a value like `10s` or `6hits`, found `{value}`")
+                    })?;
+
+                    options.advance = Some(advance);
+                }
+                "--survival" => options.advance = Some(Advance::Seconds(SURVIVAL_SECONDS)),
+                "--replay" => {
+                    let Some(path) = args.next() else {
+                        bail!("--replay needs the path of a replay file after it");
+                    };
+
+                    options.replay = Some(PathBuf::from(path));
+                }
+                "--bench-ai" => {
+                    let Some(games) = args.next() else {
+                        bail!("--bench-ai needs the number of games to play after it");
+                    };
+                    let games = games
+                        .parse()
+                        .ok()
+                        .filter(|games| *games > 0)
+                        .ok_or_else(|| {
+                            eyre!("--bench-ai expects a positive number, found `{games}`")
+                        })?;
+
+                    options.bench_games = Some(games);
+                }
+                "--bench-controller" => {
+                    let Some(name) = args.next() else {
+                        bail!("--bench-controller needs the name of a controller after it");
+                    };
+                    let controller = ControllerKind::parse(&name)
+                        .filter(ControllerKind::is_automatic)
+                        .ok_or_else(|| {
+                            eyre!(
+                                "--bench-controller expects an automatic controller, found `{name}`"
+                            )
+                        })?;
+
+                    options.bench_controller = Some(controller);
+                }
+                "--json" => options.bench_json = true,
+                _ => bail!("unknown argument `{arg}`"),
+            }
+        }
+
+        if options.bench_games.is_none()
+            && (options.bench_controller.is_some() || options.bench_json)
+        {
+            bail!("--bench-controller and --json only work together with --bench-ai");
+        }
+
+        Ok(options)
+    }
+}
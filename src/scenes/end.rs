@@ -1,27 +1,164 @@
-use anathema::component::Component;
+use anathema::{
+    component::{Component, KeyCode},
+    state::{List, State, Value},
+};
 use bb_anathema_components::BBAppComponent;
 
-use crate::app::CurrentGameScene;
+use crate::{
+    app::CurrentGameScene,
+    highscores::{GameMode, HighScore, HighScores, MAX_NAME_LENGTH},
+};
 
-pub struct EndScene;
+#[derive(Default)]
+pub struct EndScene {
+    high_scores: HighScores,
+    /// The score waiting for a name before it goes on the table.
+    pending: Option<HighScore>,
+}
 
 impl BBAppComponent for EndScene {
     fn register_to(
         builder: &mut anathema::runtime::Builder<()>,
     ) -> Result<(), anathema::runtime::Error> {
-        builder.component("end_scene", "templates/scenes/end.aml", Self, ())?;
+        builder.component(
+            "end_scene",
+            "templates/scenes/end.aml",
+            Self::default(),
+            EndSceneState::default(),
+        )?;
 
         Ok(())
     }
 }
 
+impl EndScene {
+    fn show_table(&self, mode: GameMode, state: &mut EndSceneState) {
+        let mut rows = List::empty();
+
+        for row in self.high_scores.rows(mode) {
+            rows.push(row);
+        }
+
+        state.rows.set(rows);
+    }
+
+    fn submit_name(&mut self, state: &mut EndSceneState) {
+        let Some(mut entry) = self.pending.take() else {
+            return;
+        };
+
+        let name = state.name.to_ref().trim().to_owned();
+        entry.name = if name.is_empty() {
+            "anonymous".to_owned()
+        } else {
+            name
+        };
+
+        let mode = entry.mode;
+        self.high_scores.insert(entry);
+
+        let message = match self.high_scores.save() {
+            Ok(()) => "High score saved".to_owned(),
+            Err(error) => format!("Could not save the high score: {error}"),
+        };
+
+        state.entering_name.set(false);
+        state.message.set(message);
+        self.show_table(mode, state);
+    }
+}
+
 impl Component for EndScene {
-    type State = ();
+    type State = EndSceneState;
 
     type Message = ();
 
-    fn accept_focus(&self) -> bool {
-        false
+    fn on_mount(
+        &mut self,
+        state: &mut Self::State,
+        mut _children: anathema::component::Children<'_, '_>,
+        context: anathema::component::Context<'_, '_, Self::State>,
+    ) {
+        let score = context
+            .attribute("score")
+            .and_then(|v| v.to_int())
+            .unwrap_or_default() as u32;
+        let seed = context
+            .attribute("seed")
+            .and_then(|v| v.to_int())
+            .unwrap_or_default() as u64;
+        let replaying = context
+            .attribute("replaying")
+            .and_then(|v| v.as_bool())
+            .unwrap_or_default();
+        let survival = context
+            .attribute("survival")
+            .and_then(|v| v.as_bool())
+            .unwrap_or_default();
+        let mode = GameMode::for_survival(survival);
+
+        state.name.set(String::new());
+        state.message.set(String::new());
+        self.pending = None;
+
+        match HighScores::load() {
+            Ok(high_scores) => self.high_scores = high_scores,
+            Err(error) => {
+                // a broken file is reported rather than overwritten
+                state.entering_name.set(false);
+                state
+                    .message
+                    .set(format!("Could not load high scores: {error}"));
+                state.rows.set(List::empty());
+                return;
+            }
+        }
+
+        let qualifies = !replaying && self.high_scores.qualifies(mode, score);
+
+        if qualifies {
+            self.pending = Some(HighScore {
+                name: String::new(),
+                score,
+                mode,
+                seed,
+            });
+        }
+
+        state.entering_name.set(qualifies);
+        self.show_table(mode, state);
+    }
+
+    fn on_key(
+        &mut self,
+        key: anathema::component::KeyEvent,
+        state: &mut Self::State,
+        mut _children: anathema::component::Children<'_, '_>,
+        mut _context: anathema::component::Context<'_, '_, Self::State>,
+    ) {
+        if !*state.entering_name.to_ref() {
+            return;
+        }
+
+        match key.code {
+            KeyCode::Enter => self.submit_name(state),
+            KeyCode::Backspace => {
+                let mut name = state.name.to_ref().clone();
+                name.pop();
+                state.name.set(name);
+            }
+            KeyCode::Char(character)
+                if character.is_alphanumeric() || matches!(character, ' ' | '-' | '_') =>
+            {
+                let mut name = state.name.to_ref().clone();
+
+                if name.chars().count() < MAX_NAME_LENGTH {
+                    name.push(character);
+                    state.name.set(name);
+                }
+            }
+            _ => (),
+        }
     }
 
     fn on_event(
@@ -33,6 +170,17 @@ impl Component for EndScene {
     ) {
         if event.name() == "back_to_start" {
             context.publish("change_scene", CurrentGameScene::Splash);
+        } else if event.name() == "quit_game" {
+            context.publish("quit_game", ());
         }
     }
 }
+
+#[derive(Debug, State, Default)]
+pub struct EndSceneState {
+    /// The score made the table and is waiting for a name.
+    entering_name: Value<bool>,
+    name: Value<String>,
+    message: Value<String>,
+    rows: Value<List<String>>,
+}
@@ -4,9 +4,16 @@ use anathema::{
 };
 use bb_anathema_components::BBAppComponent;
 
-use crate::app::CurrentGameScene;
+use crate::{
+    app::CurrentGameScene,
+    game::simulation::{MAX_LIVES, STARTING_LIVES},
+};
 
-pub struct GameScene;
+#[derive(Default)]
+pub struct GameScene {
+    /// Lives to go back to when the level is restarted.
+    lives_at_level_start: u8,
+}
 
 impl BBAppComponent for GameScene {
     fn register_to(
@@ -15,7 +22,7 @@ impl BBAppComponent for GameScene {
         builder.component(
             "game_scene",
             "templates/scenes/game.aml",
-            Self,
+            Self::default(),
             GameSceneState::default(),
         )?;
 
@@ -46,13 +53,52 @@ impl Component for GameScene {
             } else {
                 state.lives.set(lives - 1);
             }
+        } else if event.name() == "overrun" {
+            event.stop_propagation();
+            context.publish("change_scene", CurrentGameScene::End);
+        } else if event.name() == "extra_life" {
+            event.stop_propagation();
+            let lives = *state.lives.to_ref();
+            state.lives.set(lives.saturating_add(1).min(MAX_LIVES));
+        } else if event.name() == "power_ups" {
+            event.stop_propagation();
+            let power_ups = event.data_checked::<String>().cloned().unwrap_or_default();
+            state.power_ups.set(power_ups);
         } else if event.name() == "scored" {
             event.stop_propagation();
             let score = event.data_checked::<u32>().copied().unwrap_or_default();
             context.publish("scored", score);
-        } else if event.name() == "automation_mode_toggle" {
+        } else if event.name() == "level_cleared" {
+            event.stop_propagation();
+            let level = event.data_checked::<u8>().copied().unwrap_or_default();
+            self.lives_at_level_start = *state.lives.to_ref();
+            context.publish("level_cleared", level);
+        } else if event.name() == "toggle_pause" {
+            event.stop_propagation();
+            let paused = *state.paused.to_ref();
+            state.paused.set(!paused);
+        } else if event.name() == "resume" {
+            event.stop_propagation();
+            state.paused.set(false);
+        } else if event.name() == "restart_level" {
+            event.stop_propagation();
+            let restart_number = *state.restart_number.to_ref();
+            state.lives.set(self.lives_at_level_start);
+            state.restart_number.set(restart_number + 1);
+            state.paused.set(false);
+            context.publish("restart_level", ());
+        } else if event.name() == "quit_to_title" {
+            event.stop_propagation();
+            context.publish("change_scene", CurrentGameScene::Splash);
+        } else if event.name() == "quit_game" {
+            event.stop_propagation();
+            context.publish("quit_game", ());
+        } else if event.name() == "cycle_controller" {
+            event.stop_propagation();
+            context.publish("cycle_controller", ());
+        } else if event.name() == "toggle_aim_guide" {
             event.stop_propagation();
-            context.publish("automation_mode_toggle", ());
+            context.publish("toggle_aim_guide", ());
         }
     }
 
@@ -62,11 +108,20 @@ impl Component for GameScene {
         mut _children: anathema::component::Children<'_, '_>,
         mut _context: anathema::component::Context<'_, '_, Self::State>,
     ) {
-        state.lives.set(3);
+        self.lives_at_level_start = STARTING_LIVES;
+        state.lives.set(STARTING_LIVES);
+        state.power_ups.set(String::new());
+        state.paused.set(false);
+        state.restart_number.set(0);
     }
 }
 
 #[derive(Debug, State, Default)]
 pub struct GameSceneState {
     lives: Value<u8>,
+    /// Timers of the power-ups in effect, e.g. `wide 12s x2 3s`.
+    power_ups: Value<String>,
+    paused: Value<bool>,
+    /// Counts restarts so the game knows when to start the level over.
+    restart_number: Value<u32>,
 }
@@ -0,0 +1,96 @@
+use anathema::{
+    component::Component,
+    state::{List, State, Value},
+};
+use bb_anathema_components::BBAppComponent;
+
+use crate::{
+    app::CurrentGameScene,
+    highscores::{GameMode, HighScores},
+};
+
+pub struct HighScoresScene;
+
+impl BBAppComponent for HighScoresScene {
+    fn register_to(
+        builder: &mut anathema::runtime::Builder<()>,
+    ) -> Result<(), anathema::runtime::Error> {
+        builder.component(
+            "high_scores_scene",
+            "templates/scenes/high_scores.aml",
+            Self,
+            HighScoresSceneState::default(),
+        )?;
+
+        Ok(())
+    }
+}
+
+impl Component for HighScoresScene {
+    type State = HighScoresSceneState;
+
+    type Message = ();
+
+    fn accept_focus(&self) -> bool {
+        false
+    }
+
+    fn on_mount(
+        &mut self,
+        state: &mut Self::State,
+        mut _children: anathema::component::Children<'_, '_>,
+        context: anathema::component::Context<'_, '_, Self::State>,
+    ) {
+        let survival = context
+            .attribute("survival")
+            .and_then(|v| v.as_bool())
+            .unwrap_or_default();
+        let mode = GameMode::for_survival(survival);
+        let mut rows = List::empty();
+
+        state.mode.set(mode.as_str().to_owned());
+
+        match HighScores::load() {
+            Ok(high_scores) => {
+                let table = high_scores.rows(mode);
+                let message = if table.is_empty() {
+                    "No scores yet"
+                } else {
+                    ""
+                };
+
+                for row in table {
+                    rows.push(row);
+                }
+
+                state.message.set(message.to_owned());
+            }
+            Err(error) => state
+                .message
+                .set(format!("Could not load high scores: {error}")),
+        }
+
+        state.rows.set(rows);
+    }
+
+    fn on_event(
+        &mut self,
+        event: &mut anathema::component::UserEvent<'_>,
+        _state: &mut Self::State,
+        mut _children: anathema::component::Children<'_, '_>,
+        mut context: anathema::component::Context<'_, '_, Self::State>,
+    ) {
+        if event.name() == "back_to_start" {
+            context.publish("change_scene", CurrentGameScene::Splash);
+        } else if event.name() == "quit_game" {
+            context.publish("quit_game", ());
+        }
+    }
+}
+
+#[derive(Debug, State, Default)]
+pub struct HighScoresSceneState {
+    mode: Value<String>,
+    message: Value<String>,
+    rows: Value<List<String>>,
+}
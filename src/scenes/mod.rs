@@ -1,17 +1,26 @@
 mod end;
 mod game;
+mod high_scores;
+mod pause;
 pub mod splash;
+mod too_small;
 
 use anathema::runtime::Builder;
 use bb_anathema_components::BBAppComponent;
 use eyre::Result;
 
-use crate::scenes::{end::EndScene, game::GameScene, splash::SplashScene};
+use crate::scenes::{
+    end::EndScene, game::GameScene, high_scores::HighScoresScene, pause::PauseMenu,
+    splash::SplashScene, too_small::TooSmallScene,
+};
 
 pub fn register_scenes(builder: &mut Builder<()>) -> Result<()> {
     SplashScene::register_to(builder)?;
     GameScene::register_to(builder)?;
     EndScene::register_to(builder)?;
+    HighScoresScene::register_to(builder)?;
+    PauseMenu::register_to(builder)?;
+    TooSmallScene::register_to(builder)?;
 
     Ok(())
 }
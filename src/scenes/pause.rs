@@ -0,0 +1,74 @@
+use anathema::{
+    component::Component,
+    state::{State, Value},
+};
+use bb_anathema_components::BBAppComponent;
+
+/// Menu choices handled by the game scene rather than the menu itself.
+const FORWARDED_EVENTS: [&str; 6] = [
+    "resume",
+    "restart_level",
+    "quit_to_title",
+    "quit_game",
+    "cycle_controller",
+    "toggle_aim_guide",
+];
+
+/// Choices shown over the game while it is paused.
+pub struct PauseMenu;
+
+impl BBAppComponent for PauseMenu {
+    fn register_to(
+        builder: &mut anathema::runtime::Builder<()>,
+    ) -> Result<(), anathema::runtime::Error> {
+        builder.component(
+            "pause_menu",
+            "templates/scenes/pause.aml",
+            Self,
+            PauseMenuState::default(),
+        )?;
+
+        Ok(())
+    }
+}
+
+impl Component for PauseMenu {
+    type State = PauseMenuState;
+
+    type Message = ();
+
+    fn accept_focus(&self) -> bool {
+        false
+    }
+
+    fn on_event(
+        &mut self,
+        event: &mut anathema::component::UserEvent<'_>,
+        state: &mut Self::State,
+        mut _children: anathema::component::Children<'_, '_>,
+        mut context: anathema::component::Context<'_, '_, Self::State>,
+    ) {
+        if event.name() == "show_settings" {
+            state.settings_open.set(true);
+        } else if event.name() == "hide_settings" {
+            state.settings_open.set(false);
+        } else if let Some(name) = FORWARDED_EVENTS.iter().find(|name| **name == event.name()) {
+            event.stop_propagation();
+            context.publish(name, ());
+        }
+    }
+
+    fn on_mount(
+        &mut self,
+        state: &mut Self::State,
+        mut _children: anathema::component::Children<'_, '_>,
+        mut _context: anathema::component::Context<'_, '_, Self::State>,
+    ) {
+        state.settings_open.set(false);
+    }
+}
+
+#[derive(Debug, State, Default)]
+pub struct PauseMenuState {
+    settings_open: Value<bool>,
+}
@@ -1,22 +1,39 @@
-use anathema::component::Component;
+use std::time::Duration;
+
+use anathema::{
+    component::Component,
+    state::{State, Value},
+};
 use bb_anathema_components::BBAppComponent;
 
 use crate::app::CurrentGameScene;
 
-pub struct SplashScene;
+/// How long the title sits untouched before the demo starts.
+const IDLE_BEFORE_DEMO: Duration = Duration::from_secs(8);
+
+#[derive(Default)]
+pub struct SplashScene {
+    /// Time since the menu was last touched.
+    idle: Duration,
+}
 
 impl BBAppComponent for SplashScene {
     fn register_to(
         builder: &mut anathema::runtime::Builder<()>,
     ) -> Result<(), anathema::runtime::Error> {
-        builder.component("splash_scene", "templates/scenes/splash.aml", Self, ())?;
+        builder.component(
+            "splash_scene",
+            "templates/scenes/splash.aml",
+            Self::default(),
+            SplashSceneState::default(),
+        )?;
 
         Ok(())
     }
 }
 
 impl Component for SplashScene {
-    type State = ();
+    type State = SplashSceneState;
 
     type Message = ();
 
@@ -24,15 +41,79 @@ impl Component for SplashScene {
         false
     }
 
+    fn on_tick(
+        &mut self,
+        state: &mut Self::State,
+        mut _children: anathema::component::Children<'_, '_>,
+        mut _context: anathema::component::Context<'_, '_, Self::State>,
+        dt: Duration,
+    ) {
+        if *state.demo.to_ref() {
+            return;
+        }
+
+        self.idle += dt;
+
+        if self.idle >= IDLE_BEFORE_DEMO {
+            state.demo.set(true);
+        }
+    }
+
+    fn on_mount(
+        &mut self,
+        state: &mut Self::State,
+        mut _children: anathema::component::Children<'_, '_>,
+        mut _context: anathema::component::Context<'_, '_, Self::State>,
+    ) {
+        self.idle = Duration::ZERO;
+        state.demo.set(false);
+    }
+
     fn on_event(
         &mut self,
         event: &mut anathema::component::UserEvent<'_>,
-        _state: &mut Self::State,
+        state: &mut Self::State,
         mut _children: anathema::component::Children<'_, '_>,
         mut context: anathema::component::Context<'_, '_, Self::State>,
     ) {
-        if event.name() == "start_game" {
+        if event.name() == "end_demo" {
+            self.idle = Duration::ZERO;
+            state.demo.set(false);
+        } else if event.name() == "start_game" {
             context.publish("change_scene", CurrentGameScene::Game);
+        } else if event.name() == "show_high_scores" {
+            context.publish("change_scene", CurrentGameScene::HighScores);
+        } else if event.name() == "quit_game" {
+            context.publish("quit_game", ());
         }
     }
+
+    fn on_key(
+        &mut self,
+        _key: anathema::component::KeyEvent,
+        state: &mut Self::State,
+        mut _children: anathema::component::Children<'_, '_>,
+        mut _context: anathema::component::Context<'_, '_, Self::State>,
+    ) {
+        self.idle = Duration::ZERO;
+        state.demo.set(false);
+    }
+
+    fn on_mouse(
+        &mut self,
+        _mouse: anathema::component::MouseEvent,
+        _state: &mut Self::State,
+        mut _children: anathema::component::Children<'_, '_>,
+        mut _context: anathema::component::Context<'_, '_, Self::State>,
+    ) {
+        // moving the mouse over the menu counts as using it, only a click
+        // ends the demo
+        self.idle = Duration::ZERO;
+    }
+}
+
+#[derive(Debug, State, Default)]
+pub struct SplashSceneState {
+    /// The game is playing itself behind the title.
+    demo: Value<bool>,
 }
@@ -0,0 +1,63 @@
+use anathema::{
+    component::Component,
+    state::{State, Value},
+};
+use bb_anathema_components::BBAppComponent;
+
+use crate::app::{MIN_HEIGHT, MIN_WIDTH};
+
+/// Explains that the terminal has to grow before the game can carry on.
+pub struct TooSmallScene;
+
+impl BBAppComponent for TooSmallScene {
+    fn register_to(
+        builder: &mut anathema::runtime::Builder<()>,
+    ) -> Result<(), anathema::runtime::Error> {
+        builder.component(
+            "too_small_scene",
+            "templates/scenes/too_small.aml",
+            Self,
+            TooSmallSceneState::default(),
+        )?;
+
+        Ok(())
+    }
+}
+
+impl Component for TooSmallScene {
+    type State = TooSmallSceneState;
+
+    type Message = ();
+
+    fn accept_focus(&self) -> bool {
+        false
+    }
+
+    fn on_mount(
+        &mut self,
+        state: &mut Self::State,
+        mut _children: anathema::component::Children<'_, '_>,
+        mut _context: anathema::component::Context<'_, '_, Self::State>,
+    ) {
+        state.min_width.set(MIN_WIDTH);
+        state.min_height.set(MIN_HEIGHT);
+    }
+
+    fn on_event(
+        &mut self,
+        event: &mut anathema::component::UserEvent<'_>,
+        _state: &mut Self::State,
+        mut _children: anathema::component::Children<'_, '_>,
+        mut context: anathema::component::Context<'_, '_, Self::State>,
+    ) {
+        if event.name() == "quit_game" {
+            context.publish("quit_game", ());
+        }
+    }
+}
+
+#[derive(Debug, State, Default)]
+pub struct TooSmallSceneState {
+    min_width: Value<u16>,
+    min_height: Value<u16>,
+}
@@ -0,0 +1,36 @@
+use std::io::{self, Write};
+
+use crossterm::{
+    cursor::Show,
+    event::DisableMouseCapture,
+    execute,
+    terminal::{LeaveAlternateScreen, disable_raw_mode},
+};
+use eyre::Result;
+
+/// Install the `color_eyre` hooks, with a panic hook that puts the terminal
+/// back the way it was before the report is printed, otherwise the report
+/// ends up on the alternate screen with raw mode still on.
+pub fn install_hooks() -> Result<()> {
+    let (panic_hook, eyre_hook) = color_eyre::config::HookBuilder::default().into_hooks();
+
+    eyre_hook.install()?;
+
+    std::panic::set_hook(Box::new(move |info| {
+        // the report matters more than a terminal that can't be restored
+        let _ = restore();
+        eprintln!("{}", panic_hook.panic_report(info));
+    }));
+
+    Ok(())
+}
+
+/// Leave the alternate screen, show the cursor and stop capturing the mouse
+/// and raw input. Safe to call when the terminal was never set up.
+pub fn restore() -> io::Result<()> {
+    let mut stdout = io::stdout();
+
+    execute!(stdout, DisableMouseCapture, LeaveAlternateScreen, Show)?;
+    disable_raw_mode()?;
+    stdout.flush()
+}